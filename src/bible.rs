@@ -50,6 +50,13 @@ impl<Content: Debug + Default> BibleBookOrganizer<Content> {
                 .map(move|(&verse, content)| ChapterVerse::new(chapter, verse).with_content(content))
         })
     }
+
+    /// - Every verse that has content, in order
+    pub fn iter_all_content(&self) -> impl Iterator<Item = PassageContent<'_, ChapterVerse, Content>> {
+        self.chapter_verse.iter().flat_map(|(&chapter, map)| {
+            map.iter().map(move |(&verse, content)| ChapterVerse::new(chapter, verse).with_content(content))
+        })
+    }
 }
 
 
@@ -1,6 +1,6 @@
 use itertools::Either;
 
-use crate::{compare::{BookPassageContent, PassageContent, SegmentCompare}, organizer::PassageOrganizer, passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange, full_chapter::FullChapter, full_chapter_range::FullChapterRange}, segment::PassageSegment, book_segment::BookSegment};
+use crate::{canon::BookFilter, compare::{BookPassageContent, PassageContent, SegmentCompare}, organizer::PassageOrganizer, passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange, full_chapter::FullChapter, full_chapter_range::FullChapterRange}, segment::PassageSegment, book_segment::BookSegment};
 use std::{collections::BTreeMap, fmt::Debug, ops::{Deref, DerefMut}};
 
 /// This is meant to organize content across the entire Bible
@@ -75,6 +75,21 @@ impl<Container: Debug + Default> FullBibleOrganizer<Container> {
 
 }
 
+// Filters
+// - Ex: only the New Testament, or only [`NamedRange::Wisdom`](crate::canon::NamedRange::Wisdom)
+impl<Container: Debug + Default> FullBibleOrganizer<Container> {
+    pub fn iter_books(&self, filter: impl BookFilter) -> impl Iterator<Item = (u8, &PassageOrganizer<Container>)> {
+        self.0.iter()
+            .filter(move |(&book, _)| filter.includes_book(book))
+            .map(|(&book, org)| (book, org))
+    }
+
+    /// - Removes every book that does not pass the filter
+    pub fn retain_books(&mut self, filter: impl BookFilter) {
+        self.0.retain(|&book, _| filter.includes_book(book));
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct BookGroupedContent<'a, Container: Debug + Default> {
     pub chapter_verse: Vec<BookPassageContent<'a, ChapterVerse, Container>>,
@@ -124,10 +139,25 @@ impl<Container: Debug + Default> FullBibleOrganizer<Container> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{compare::SegmentCompare, parse::ParsableSegment, passage_segments::chapter_verse::ChapterVerse};
+    use crate::{book_segment::BookSegment, canon::{NamedRange, Testament}, compare::SegmentCompare, parse::ParsableSegment, passage_segments::chapter_verse::ChapterVerse};
 
     use super::FullBibleOrganizer;
 
+    #[test]
+    fn filters() {
+        let mut bible = FullBibleOrganizer::<String>::new();
+        *bible.modify(BookSegment::chapter_verse(1, 1, 1)) = String::from("In the beginning God created the heavens and the earth.");
+        *bible.modify(BookSegment::chapter_verse(19, 23, 1)) = String::from("The Lord is my shepherd; I shall not want.");
+        *bible.modify(BookSegment::chapter_verse(43, 1, 1)) = String::from("In the beginning was the Word, and the Word was with God, and the Word was God.");
+
+        assert_eq!(bible.iter_books(Testament::New).map(|(book, _)| book).collect::<Vec<_>>(), vec![43]);
+        assert_eq!(bible.iter_books(NamedRange::Wisdom).map(|(book, _)| book).collect::<Vec<_>>(), vec![19]);
+
+        bible.retain_books(Testament::Old);
+        assert_eq!(bible.iter_books(|_| true).map(|(book, _)| book).collect::<Vec<_>>(), vec![1, 19]);
+        assert!(bible.get_chapter_verse_content(&BookSegment::chapter_verse(43, 1, 1)).is_empty());
+    }
+
     #[test]
     fn test() -> Result<(), String> {
         let mut bible = FullBibleOrganizer::<String>::new();
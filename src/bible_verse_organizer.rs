@@ -1,13 +1,18 @@
 use itertools::Either;
+use once_cell::sync::Lazy;
+use regex::Regex;
 
-use crate::{bible::BibleBookOrganizer, compare::{BookPassageContent, PassageContent, SegmentCompare}, passage_segments::chapter_verse::ChapterVerse, book_segment::BookSegment};
-use std::{collections::BTreeMap, fmt::Debug};
+use crate::{bible::BibleBookOrganizer, book_chapter_verse::BookChapterVerse, book_manager::BookManager, canon::BookFilter, compare::{BookPassageContent, PassageContent, SegmentCompare}, passage_segments::chapter_verse::ChapterVerse, book_segment::BookSegment};
+use std::{collections::BTreeMap, fmt::Debug, path::Path};
 
 #[derive(Debug, Default)]
 pub struct BibleVerseOrganizer<Content: Debug + Default> {
     chapter_verse: BTreeMap<u8, BibleBookOrganizer<Content>>,
 }
 
+/// - The `John 3:16` (or `1 Jn.3.16`) at the start of a line of [`BibleVerseOrganizer::from_text`]
+static LEADING_VERSE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^((?:[1-3] ?)?\D*?)\s*(\d+)[:.](\d+)(?:\s|$)").unwrap());
+
 impl<Content: Debug + Default> BibleVerseOrganizer<Content> {
     pub fn new() -> Self {
         Self {
@@ -42,12 +47,134 @@ impl<Content: Debug + Default> BibleVerseOrganizer<Content> {
     }
 }
 
+// Filters
+// - Ex: only the New Testament, or only [`NamedRange::Wisdom`](crate::canon::NamedRange::Wisdom)
+impl<Content: Debug + Default> BibleVerseOrganizer<Content> {
+    pub fn iter_books(&self, filter: impl BookFilter) -> impl Iterator<Item = (u8, &BibleBookOrganizer<Content>)> {
+        self.chapter_verse.iter()
+            .filter(move |(&book, _)| filter.includes_book(book))
+            .map(|(&book, org)| (book, org))
+    }
+
+    /// - Every verse with content in the books that pass the filter, in order
+    pub fn iter_filtered_content(&self, filter: impl BookFilter) -> impl Iterator<Item = BookPassageContent<'_, ChapterVerse, Content>> {
+        self.iter_books(filter)
+            .flat_map(|(book, org)| org.iter_all_content().map(move |psg| psg.with_book(book)))
+    }
+
+    /// - Removes every book that does not pass the filter
+    pub fn retain_books(&mut self, filter: impl BookFilter) {
+        self.chapter_verse.retain(|&book, _| filter.includes_book(book));
+    }
+}
+
+// Loading
+// - For Bibles stored in local files, with one string of content per verse
+impl BibleVerseOrganizer<String> {
+    /// - A JSON object of `BBCCCVVV` verse ids to content, like `{ "43003016": "For God so loved the world, ..." }`
+    /// - Returns an error for invalid JSON, ids, or content that is not a string
+    pub fn from_verse_id_json(input: &str) -> Result<Self, String> {
+        let verses: BTreeMap<String, String> = serde_json::from_str(input)
+            .map_err(|e| format!("Expected a JSON object of verse ids to content: {}", e))?;
+        let mut bible = Self::new();
+        for (id, content) in verses {
+            let number = id.trim().parse::<u32>().ok().filter(|_| id.trim().len() == 8)
+                .ok_or_else(|| format!("Expected an 8 digit 'BBCCCVVV' verse id, found '{}'", id))?;
+            let [book, chapter, verse] = [number / 1_000_000, number / 1_000 % 1_000, number % 1_000].map(u8::try_from);
+            let key = match (book, chapter, verse) {
+                (Ok(book), Ok(chapter), Ok(verse)) => BookChapterVerse::new(book, chapter, verse).ok(),
+                _ => None,
+            }.ok_or_else(|| format!("There is no verse with the id '{}'", id))?;
+            *bible.modify(*key) = content;
+        }
+        Ok(bible)
+    }
+
+    /// - Files ending in `.json` are loaded with [`BibleVerseOrganizer::from_verse_id_json`], and anything else with [`BibleVerseOrganizer::from_text`]
+    /// - Returns an error if the file can not be read or loaded
+    pub fn load(manager: &BookManager, path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path.display(), e))?;
+        let is_json = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::from_verse_id_json(&input)
+        } else {
+            Self::from_text(manager, &input)
+        }.map_err(|e| format!("Could not load '{}': {}", path.display(), e))
+    }
+
+    /// - One verse per line, starting with its reference: `John 3:16 For God so loved the world, ...`
+    /// - Blank lines and lines starting with `#` are skipped
+    /// - Returns an error (with the line number) for lines that do not start with a single valid verse
+    /// - Only the reference at the start of the line is parsed, so the verse text can hold anything
+    pub fn from_text(manager: &BookManager, input: &str) -> Result<Self, String> {
+        let mut bible = Self::new();
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || format!("Line {}: expected a verse followed by its content, like 'John 3:16 For God ...'", idx + 1);
+            let captures = LEADING_VERSE.captures(line).ok_or_else(error)?;
+            let book = manager.book_id(captures[1].trim()).ok_or_else(error)?;
+            let (chapter, verse) = captures[2].parse().ok().zip(captures[3].parse().ok()).ok_or_else(error)?;
+            let key = BookChapterVerse::new(book, chapter, verse)
+                .map_err(|e| format!("Line {}: {}", idx + 1, e))?;
+            *bible.modify(*key) = line[captures[0].len()..].trim().to_string();
+        }
+        Ok(bible)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{compare::SegmentCompare, passage_segments::{chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange}, book_segment::BookSegment};
+    use crate::{book_manager::DEFAULT_BOOK_MANAGER, canon::{NamedRange, Testament}, compare::SegmentCompare, passage_segments::{chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange}, book_segment::BookSegment};
 
     use super::BibleVerseOrganizer;
 
+    #[test]
+    fn filters() {
+        let mut bible = BibleVerseOrganizer::<String>::new();
+        *bible.modify(BookSegment::chapter_verse(1, 1, 1)) = String::from("In the beginning God created the heavens and the earth.");
+        *bible.modify(BookSegment::chapter_verse(19, 23, 1)) = String::from("The Lord is my shepherd; I shall not want.");
+        *bible.modify(BookSegment::chapter_verse(43, 1, 2)) = String::from("He was in the beginning with God.");
+        *bible.modify(BookSegment::chapter_verse(43, 1, 1)) = String::from("In the beginning was the Word, and the Word was with God, and the Word was God.");
+
+        let new_testament = bible.iter_filtered_content(Testament::New)
+            .map(|psg| (psg.book, psg.segment))
+            .collect::<Vec<_>>();
+        assert_eq!(new_testament, vec![(43, ChapterVerse::new(1, 1)), (43, ChapterVerse::new(1, 2))]);
+
+        assert_eq!(bible.iter_books(NamedRange::Wisdom).map(|(book, _)| book).collect::<Vec<_>>(), vec![19]);
+
+        bible.retain_books(Testament::Old);
+        assert_eq!(bible.iter_books(|_| true).map(|(book, _)| book).collect::<Vec<_>>(), vec![1, 19]);
+    }
+
+    #[test]
+    fn load() {
+        let bible = BibleVerseOrganizer::from_verse_id_json(r#"{ "43003016": "For God so loved the world,", "01001001": "In the beginning" }"#).unwrap();
+        let verses = bible.iter_filtered_content(|_| true).map(|psg| (psg.book, psg.segment, psg.content.as_str())).collect::<Vec<_>>();
+        assert_eq!(verses, vec![(1, ChapterVerse::new(1, 1), "In the beginning"), (43, ChapterVerse::new(3, 16), "For God so loved the world,")]);
+        assert!(BibleVerseOrganizer::from_verse_id_json(r#"{ "4303016": "" }"#).is_err());
+        assert!(BibleVerseOrganizer::from_verse_id_json(r#"{ "43300016": "" }"#).is_err());
+        assert!(BibleVerseOrganizer::from_verse_id_json(r#"{ "43003016": 1 }"#).is_err());
+
+        let text = "# ESV\nJohn 3:16 For God so loved the world,\n\nJn 3:17 For God did not send his Son\n1 Jn.1.1 I am 300 years old, Gen 1-2-3\n";
+        let bible = BibleVerseOrganizer::from_text(&DEFAULT_BOOK_MANAGER, text).unwrap();
+        let verses = bible.iter_filtered_content(|_| true).map(|psg| (psg.segment, psg.content.as_str())).collect::<Vec<_>>();
+        assert_eq!(verses, vec![
+            (ChapterVerse::new(3, 16), "For God so loved the world,"),
+            (ChapterVerse::new(3, 17), "For God did not send his Son"),
+            (ChapterVerse::new(1, 1), "I am 300 years old, Gen 1-2-3"),
+        ]);
+        assert!(BibleVerseOrganizer::from_text(&DEFAULT_BOOK_MANAGER, "For God so loved").is_err());
+        assert!(BibleVerseOrganizer::from_text(&DEFAULT_BOOK_MANAGER, "John 3:16-17 For God").is_err());
+        assert!(BibleVerseOrganizer::from_text(&DEFAULT_BOOK_MANAGER, "John 30:1 For God").is_err());
+        assert!(BibleVerseOrganizer::from_text(&DEFAULT_BOOK_MANAGER, "Johnny 3:16 For God").is_err());
+        assert!(BibleVerseOrganizer::from_text(&DEFAULT_BOOK_MANAGER, "John 3:300 For God").is_err());
+    }
+
     #[test]
     fn test() {
         let mut bible = BibleVerseOrganizer::<String>::new();
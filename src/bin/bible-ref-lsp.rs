@@ -0,0 +1,519 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    process::ExitCode,
+};
+
+use bible_reference_parser::{
+    bible_verse_organizer::BibleVerseOrganizer,
+    book_chapter_verse::{chapter_count, verse_count},
+    book_manager::BookManager,
+    citation::{CitationStyle, ReferenceFormatter},
+    extract::{ReferenceMatch, WORD_ABBREVIATIONS},
+    passage::PassageFormatter,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Value};
+
+const USAGE: &str = "\
+Usage: bible-ref-lsp [OPTIONS]
+
+A language server for Bible references, over stdio
+
+Options:
+  --bible <FILE>    Verse text for hovers: a JSON object of `BBCCCVVV` verse ids to text, or lines like `John 3:16 For God ...`
+  --style <STYLE>   The house style for code actions: sbl, chicago, apa, plain (default), or abbreviated
+  -h, --help        Print this message
+
+The same settings can be sent as `initializationOptions`: { \"bible\": \"./esv.json\", \"style\": \"sbl\" }";
+
+/// - Something that looks like a reference (`Name 3:16`), so misspelled book names can be reported
+static REFERENCE_LIKE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b((?:[1-3] ?)?[A-Z][a-z]+)\.? ?\d+[:.]\d+").unwrap());
+
+static COMPLETE_VERSE: Lazy<Regex> = Lazy::new(|| Regex::new(r"((?:[1-3] ?)?[A-Za-z]+)\.? ?(\d+)[:.](\d*)$").unwrap());
+static COMPLETE_CHAPTER: Lazy<Regex> = Lazy::new(|| Regex::new(r"((?:[1-3] ?)?[A-Za-z]+)(?:\. ?| )(\d*)$").unwrap());
+static COMPLETE_BOOK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:[1-3] ?)?[A-Za-z]*$").unwrap());
+
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const COMPLETION_VALUE: u8 = 12;
+const COMPLETION_REFERENCE: u8 = 18;
+
+/// - The edits (insert, delete, replace, or swap two neighbours) to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            rows[i][j] = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                rows[i][j] = rows[i][j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// - Reads one `Content-Length` framed message, or `None` at the end of the input
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>, String> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header).map_err(|e| e.to_string())? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse::<usize>().map_err(|e| format!("Invalid Content-Length: {}", e))?);
+            }
+        }
+    }
+    let length = length.ok_or("Missing Content-Length header")?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&body).map(Some).map_err(|e| format!("Invalid message: {}", e))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<(), String> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body).map_err(|e| e.to_string())?;
+    output.flush().map_err(|e| e.to_string())
+}
+
+/// - LSP positions count UTF-16 code units from the start of the line
+fn position(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    json!({
+        "line": before.matches('\n').count(),
+        "character": before[line_start..].encode_utf16().count(),
+    })
+}
+
+fn range(text: &str, start: usize, end: usize) -> Value {
+    json!({ "start": position(text, start), "end": position(text, end) })
+}
+
+/// - Positions past the end of a line are clamped to the end of that line
+fn offset(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+    let line_start = match line {
+        0 => 0,
+        _ => text.match_indices('\n').nth(line - 1).map_or(text.len(), |(idx, _)| idx + 1),
+    };
+    let line_text = text[line_start..].split('\n').next().unwrap_or("");
+    let mut units = 0;
+    for (idx, c) in line_text.char_indices() {
+        if units >= character {
+            return line_start + idx;
+        }
+        units += c.len_utf16();
+    }
+    line_start + line_text.len()
+}
+
+struct Server {
+    manager: BookManager,
+    style: CitationStyle,
+    bible: Option<BibleVerseOrganizer<String>>,
+    documents: HashMap<String, String>,
+    is_shut_down: bool,
+}
+
+impl Server {
+    fn new() -> Self {
+        Self {
+            manager: BookManager::default(),
+            style: CitationStyle::default(),
+            bible: None,
+            documents: HashMap::new(),
+            is_shut_down: false,
+        }
+    }
+
+    fn configure(&mut self, bible: Option<&str>, style: Option<&str>) -> Result<(), String> {
+        if let Some(style) = style {
+            self.style = style.parse()?;
+        }
+        if let Some(path) = bible {
+            self.bible = Some(BibleVerseOrganizer::load(&self.manager, path)?);
+        }
+        Ok(())
+    }
+
+    /// - The written book is a lowercase abbreviation that is also a word (`is 6`)
+    fn is_ambiguous(&self, reference: &ReferenceMatch) -> bool {
+        let book = reference.text.trim_end_matches(|c: char| !c.is_alphabetic()).split(|c: char| !c.is_alphabetic()).next().unwrap_or("");
+        book.chars().all(|c| c.is_lowercase()) && WORD_ABBREVIATIONS.contains(&book)
+    }
+
+    /// - The book name that `name` is one or two typos away from (`Philipians`), so `Python 3.11` and `Noon 12:30` are left alone
+    fn near_miss(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        let allowed = if name.chars().count() < 7 { 1 } else { 2 };
+        self.manager.book_id_to_name.values()
+            .map(|book| (edit_distance(&name, &book.to_lowercase()), book))
+            .filter(|(distance, _)| *distance <= allowed)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, book)| book.as_str())
+    }
+
+    fn diagnostics(&self, text: &str) -> Vec<Value> {
+        let references = self.manager.extract_references(text);
+        let mut diagnostics: Vec<Value> = references.iter().filter_map(|reference| {
+            let (severity, message) = match reference.passage.normalize() {
+                Err(e) => (SEVERITY_ERROR, format!("Invalid reference '{}': {}", reference.text, e)),
+                Ok(_) if self.is_ambiguous(reference) => (SEVERITY_WARNING, format!("'{}' might not be a reference; write the book name to be sure", reference.text)),
+                Ok(_) => return None,
+            };
+            Some(json!({
+                "range": range(text, reference.span.start, reference.span.end),
+                "severity": severity,
+                "source": "bible-ref",
+                "message": message,
+            }))
+        }).collect();
+
+        for captures in REFERENCE_LIKE.captures_iter(text) {
+            let (whole, book) = (captures.get(0).unwrap(), captures.get(1).unwrap());
+            let is_found = references.iter().any(|reference| reference.span.contains(&whole.start()) || whole.range().contains(&reference.span.start));
+            if is_found || self.manager.book_id(book.as_str()).is_some() {
+                continue;
+            }
+            if let Some(suggestion) = self.near_miss(book.as_str()) {
+                diagnostics.push(json!({
+                    "range": range(text, whole.start(), whole.end()),
+                    "severity": SEVERITY_WARNING,
+                    "source": "bible-ref",
+                    "message": format!("Unknown book '{}'; did you mean '{}'?", book.as_str(), suggestion),
+                }));
+            }
+        }
+        diagnostics
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let diagnostics = self.documents.get(uri).map(|text| self.diagnostics(text)).unwrap_or_default();
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    fn reference_at<'a>(&self, text: &'a str, offset: usize) -> Option<ReferenceMatch<'a>> {
+        self.manager.extract_references(text).into_iter()
+            .find(|reference| reference.span.start <= offset && offset <= reference.span.end)
+    }
+
+    fn hover(&self, text: &str, offset: usize) -> Value {
+        let Some(reference) = self.reference_at(text, offset) else {
+            return Value::Null;
+        };
+        let contents = match reference.passage.normalize().and_then(|passage| Ok((self.style.format_passage(&self.manager, &passage)?, passage))) {
+            Err(e) => format!("Invalid reference: {}", e),
+            Ok((label, passage)) => {
+                let formatter = PassageFormatter::new("{content}", "{content}", "{content}", "<sup>{verse}</sup> {content}")
+                    .map(|formatter| formatter.with_joiners(" ", " ", "\n\n"));
+                let quote = match (&self.bible, formatter) {
+                    (Some(bible), Ok(formatter)) => passage.render(&self.manager, bible, &formatter).ok().filter(|quote| !quote.is_empty()),
+                    _ => None,
+                };
+                match quote {
+                    Some(quote) => format!("**{}**\n\n{}", label, quote.lines().map(|line| format!("> {}", line)).collect::<Vec<_>>().join("\n")),
+                    None => format!("**{}**", label),
+                }
+            }
+        };
+        json!({
+            "contents": { "kind": "markdown", "value": contents },
+            "range": range(text, reference.span.start, reference.span.end),
+        })
+    }
+
+    fn completion(&self, text: &str, offset: usize) -> Value {
+        let line_start = text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let before = &text[line_start..offset];
+        let numbers = |count: u8| -> Value {
+            (1..=count).map(|n| json!({ "label": n.to_string(), "kind": COMPLETION_VALUE, "sortText": format!("{:03}", n) })).collect()
+        };
+
+        if let Some(captures) = COMPLETE_VERSE.captures(before) {
+            let book = self.manager.book_id(&captures[1]);
+            let chapter = captures[2].parse::<u8>().ok();
+            if let Some(count) = book.zip(chapter).and_then(|(book, chapter)| verse_count(book, chapter)) {
+                return numbers(count);
+            }
+        }
+        if let Some(captures) = COMPLETE_CHAPTER.captures(before) {
+            if let Some(count) = self.manager.book_id(&captures[1]).and_then(chapter_count) {
+                return numbers(count);
+            }
+        }
+        let prefix = COMPLETE_BOOK.find(before).map_or("", |prefix| prefix.as_str()).to_lowercase();
+        self.manager.book_id_to_name.iter()
+            .filter(|(book, name)| {
+                name.to_lowercase().starts_with(&prefix)
+                    || self.manager.abbreviations_to_book_id.iter().any(|(abbreviation, id)| id == *book && abbreviation.starts_with(&prefix))
+            })
+            .map(|(book, name)| json!({ "label": name, "kind": COMPLETION_REFERENCE, "sortText": format!("{:02}", book) }))
+            .collect()
+    }
+
+    fn code_actions(&self, uri: &str, text: &str, start: usize, end: usize) -> Value {
+        self.manager.extract_references(text).into_iter()
+            .filter(|reference| reference.span.start <= end && start <= reference.span.end)
+            .filter_map(|reference| {
+                let normalized = self.style.format_passage(&self.manager, &reference.passage.normalize().ok()?).ok()?;
+                if normalized == reference.text {
+                    return None;
+                }
+                Some(json!({
+                    "title": format!("Normalize to '{}'", normalized),
+                    "kind": "quickfix",
+                    "edit": { "changes": { uri: [{ "range": range(text, reference.span.start, reference.span.end), "newText": normalized }] } },
+                }))
+            })
+            .collect()
+    }
+
+    /// - Returns every message to send back (responses and notifications)
+    fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or("");
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+
+        let result: Result<Value, (i32, String)> = match method {
+            "initialize" => {
+                let options = &params["initializationOptions"];
+                self.configure(options["bible"].as_str(), options["style"].as_str())
+                    .map_err(|e| (-32602, e))
+                    .map(|_| json!({
+                        "capabilities": {
+                            "textDocumentSync": 1,
+                            "hoverProvider": true,
+                            "completionProvider": { "triggerCharacters": [" ", ":", "."] },
+                            "codeActionProvider": true,
+                        },
+                        "serverInfo": { "name": "bible-ref-lsp", "version": env!("CARGO_PKG_VERSION") },
+                    }))
+            }
+            "shutdown" => {
+                self.is_shut_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/didOpen" => {
+                self.documents.insert(uri.clone(), params["textDocument"]["text"].as_str().unwrap_or("").to_string());
+                return vec![self.publish_diagnostics(&uri)];
+            }
+            "textDocument/didChange" => {
+                // full sync, so the last change is the whole document
+                if let Some(text) = params["contentChanges"].as_array().and_then(|changes| changes.last()).and_then(|change| change["text"].as_str()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                return vec![self.publish_diagnostics(&uri)];
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![self.publish_diagnostics(&uri)];
+            }
+            "textDocument/hover" | "textDocument/completion" | "textDocument/codeAction" => {
+                let text = self.documents.get(&uri).map(String::as_str).unwrap_or("");
+                Ok(match method {
+                    "textDocument/hover" => self.hover(text, offset(text, &params["position"])),
+                    "textDocument/completion" => self.completion(text, offset(text, &params["position"])),
+                    _ => self.code_actions(&uri, text, offset(text, &params["range"]["start"]), offset(text, &params["range"]["end"])),
+                })
+            }
+            _ => Err((-32601, format!("Method not found: {}", method))),
+        };
+
+        // notifications do not get responses
+        let Some(id) = id else {
+            return vec![];
+        };
+        vec![match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+        }]
+    }
+}
+
+fn run(args: Vec<String>) -> Result<ExitCode, String> {
+    let mut server = Server::new();
+    let (mut bible, mut style) = (None, None);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bible" => bible = Some(args.next().ok_or("Missing value for '--bible'")?),
+            "--style" => style = Some(args.next().ok_or("Missing value for '--style'")?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(ExitCode::SUCCESS);
+            }
+            // editors often pass `--stdio`, which is the only transport anyway
+            "--stdio" => {}
+            other => Err(format!("Unknown option '{}'\n\n{}", other, USAGE))?,
+        }
+    }
+    server.configure(bible.as_deref(), style.as_deref())?;
+
+    let (stdin, stdout) = (std::io::stdin(), std::io::stdout());
+    let (mut input, mut output) = (stdin.lock(), stdout.lock());
+    while let Some(message) = read_message(&mut input)? {
+        if message["method"] == "exit" {
+            return Ok(if server.is_shut_down { ExitCode::SUCCESS } else { ExitCode::FAILURE });
+        }
+        for response in server.handle(&message) {
+            write_message(&mut output, &response)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    run(std::env::args().skip(1).collect()).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::from(2)
+    })
+}
+
+#[cfg(test)]
+mod bible_ref_lsp_tests {
+    use std::io::Cursor;
+
+    use bible_reference_parser::{bible_verse_organizer::BibleVerseOrganizer, book_manager::BookManager};
+    use serde_json::{json, Value};
+
+    use super::{offset, position, read_message, write_message, Server};
+
+    fn server(text: &str) -> Server {
+        let mut server = Server::new();
+        server.bible = Some(BibleVerseOrganizer::from_text(&BookManager::default(), "John 3:16 For God so loved the world,\nJohn 3:17 For God did not send his Son").unwrap());
+        server.documents.insert(String::from("file:///notes.md"), text.to_string());
+        server
+    }
+
+    fn request(server: &mut Server, method: &str, params: Value) -> Value {
+        server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })).remove(0)["result"].clone()
+    }
+
+    #[test]
+    fn framing() {
+        let mut output = vec![];
+        write_message(&mut output, &json!({ "id": 1 })).unwrap();
+        write_message(&mut output, &json!({ "id": "é" })).unwrap();
+        let mut input = Cursor::new(output);
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "id": 1 })));
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "id": "é" })));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn positions() {
+        let text = "one\n😀 John 3:16";
+        let john = text.find("John").unwrap();
+        assert_eq!(position(text, john), json!({ "line": 1, "character": 3 }));
+        assert_eq!(offset(text, &json!({ "line": 1, "character": 3 })), john);
+        assert_eq!(offset(text, &json!({ "line": 0, "character": 99 })), 3);
+    }
+
+    #[test]
+    fn diagnostics() {
+        let server = server("");
+        let messages = |text: &str| -> Vec<String> {
+            server.diagnostics(text).iter().map(|diagnostic| diagnostic["message"].as_str().unwrap().to_string()).collect()
+        };
+        assert_eq!(messages("Read John 3:16, John 30:1, is 6:1 and Philipians 4:13.\nIsaiah 6, Jhon 3:16"), vec![
+            "Invalid reference 'John 30:1': There is no 'Chapter 30' in 'Book 43'",
+            "'is 6:1' might not be a reference; write the book name to be sure",
+            "Unknown book 'Philipians'; did you mean 'Philippians'?",
+            "Unknown book 'Jhon'; did you mean 'John'?",
+        ]);
+
+        // prose is not a reference, and does not stop the server
+        assert!(messages("I am 300 years old. See John 3.16.17 and Gen 1-2-3 in Acts 2024.").is_empty());
+        assert!(messages("Upgrade to Python 3.11 at Noon 12:30, or Hezekiah 3:16").is_empty());
+    }
+
+    #[test]
+    fn hover() {
+        let mut server = server("See Jn 3:16-17 here");
+        let hover = request(&mut server, "textDocument/hover", json!({ "textDocument": { "uri": "file:///notes.md" }, "position": { "line": 0, "character": 6 } }));
+        assert_eq!(hover["contents"]["value"], "**John 3:16-17**\n\n> <sup>16</sup> For God so loved the world, <sup>17</sup> For God did not send his Son");
+        assert_eq!(hover["range"], json!({ "start": { "line": 0, "character": 4 }, "end": { "line": 0, "character": 14 } }));
+
+        let hover = request(&mut server, "textDocument/hover", json!({ "textDocument": { "uri": "file:///notes.md" }, "position": { "line": 0, "character": 1 } }));
+        assert_eq!(hover, Value::Null);
+    }
+
+    #[test]
+    fn completion() {
+        let labels = |text: &str| -> Vec<String> {
+            let mut server = server(text);
+            let character = text.chars().count();
+            let items = request(&mut server, "textDocument/completion", json!({ "textDocument": { "uri": "file:///notes.md" }, "position": { "line": 0, "character": character } }));
+            items.as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap().to_string()).collect()
+        };
+        assert_eq!(labels("See Jude ").len(), 1);
+        assert_eq!(labels("See John 3:").len(), 36);
+        assert_eq!(labels("See Ps. ").len(), 150);
+        assert_eq!(labels("See Phil"), vec!["Philippians", "Philemon"]);
+        assert!(labels("See Phil").iter().all(|label| label.starts_with("Phil")));
+    }
+
+    #[test]
+    fn code_actions() {
+        let mut server = server("See Jn.3.16 and John 3:17\nJohn 5, 3:16");
+        let actions = request(&mut server, "textDocument/codeAction", json!({
+            "textDocument": { "uri": "file:///notes.md" },
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 25 } },
+        }));
+        assert_eq!(actions, json!([{
+            "title": "Normalize to 'John 3:16'",
+            "kind": "quickfix",
+            "edit": { "changes": { "file:///notes.md": [{
+                "range": { "start": { "line": 0, "character": 4 }, "end": { "line": 0, "character": 11 } },
+                "newText": "John 3:16",
+            }] } },
+        }]));
+
+        // the whole of chapter 5 must not become verse 5 of chapter 3
+        let actions = request(&mut server, "textDocument/codeAction", json!({
+            "textDocument": { "uri": "file:///notes.md" },
+            "range": { "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 0 } },
+        }));
+        assert_eq!(actions[0]["title"], "Normalize to 'John 3:16; 5:1-47'");
+    }
+
+    #[test]
+    fn lifecycle() {
+        let mut server = Server::new();
+        let response = server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "initializationOptions": { "style": "sbl" } } }));
+        assert_eq!(response[0]["result"]["capabilities"]["hoverProvider"], true);
+
+        let published = server.handle(&json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": { "uri": "file:///a.md", "text": "John 30:1" } } }));
+        assert_eq!(published[0]["params"]["diagnostics"].as_array().unwrap().len(), 1);
+
+        let unknown = server.handle(&json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/unknown" }));
+        assert_eq!(unknown[0]["error"]["code"], -32601);
+        assert!(server.handle(&json!({ "jsonrpc": "2.0", "method": "$/unknown" })).is_empty());
+
+        server.handle(&json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }));
+        assert!(server.is_shut_down);
+    }
+}
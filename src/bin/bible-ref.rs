@@ -0,0 +1,381 @@
+use std::{io::Read, process::ExitCode};
+
+use bible_reference_parser::{
+    bible_verse_organizer::BibleVerseOrganizer,
+    book_manager::{BookManager, BookWithAbbreviationsList},
+    citation::{CitationStyle, ReferenceFormatter},
+    extract::ReferenceMatch,
+    passage::{PassageFormatter, Template},
+    segments::BookPassageSegments,
+};
+use serde_json::{json, Value};
+
+const USAGE: &str = "\
+Usage: bible-ref <COMMAND> [OPTIONS] [ARGS]
+
+Commands:
+  parse <REFERENCE>       Print a reference as JSON or text
+  extract [FILE]          List every reference in a file (or stdin) with its line and column
+  normalize [FILE]        Rewrite every reference in a file (or stdin) in the chosen style
+  validate [REFERENCE]... Exit with 1 if any reference is invalid (reads stdin when none are given)
+  show <REFERENCE>        Print the text of a passage from the --bible file
+
+Options:
+  --locale <en|FILE>      Book names and abbreviations: `en`, or a JSON file like data/books_with_abbreviations.json
+  --style <STYLE>         sbl, chicago, apa, plain (default), or abbreviated
+  --format <FORMAT>       json or text (default); `show` also has plain (the same as text), numbered, and markdown
+  --bible <FILE>          For `show`: a JSON object of `BBCCCVVV` verse ids to text, or lines like `John 3:16 For God ...`
+  --template <TEMPLATE>   For `show`: how to print each verse, with {book}, {chapter}, {verse}, {label}, and {content}
+  -h, --help              Print this message
+
+Exit codes: 0 on success, 1 when a reference is invalid or not found, 2 for usage and file errors";
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+    /// - `[3:16] For God ...` on each line
+    Numbered,
+    /// - A blockquote with verse numbers and the reference at the end
+    Markdown,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Options {
+    command: String,
+    locale: Option<String>,
+    style: CitationStyle,
+    format: Format,
+    bible: Option<String>,
+    template: Option<Template>,
+    args: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            // supports both `--style sbl` and `--style=sbl`
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = || inline_value.clone().or_else(|| args.next()).ok_or_else(|| format!("Missing value for '{}'", flag));
+            match flag.as_str() {
+                "--locale" => options.locale = Some(value()?),
+                "--style" => options.style = value()?.parse()?,
+                "--format" => options.format = match value()?.as_str() {
+                    "json" => Format::Json,
+                    "text" | "plain" => Format::Text,
+                    "numbered" => Format::Numbered,
+                    "markdown" | "md" => Format::Markdown,
+                    other => Err(format!("'{}' is not a known format (json, text, plain, numbered, markdown)", other))?,
+                },
+                "--bible" => options.bible = Some(value()?),
+                "--template" => options.template = Some(value()?.parse()?),
+                "-h" | "--help" => options.command = String::from("help"),
+                _ if flag.starts_with("--") => Err(format!("Unknown option '{}'", flag))?,
+                _ if options.command.is_empty() => options.command = arg,
+                _ => options.args.push(arg),
+            }
+        }
+        Ok(options)
+    }
+
+    fn book_manager(&self) -> Result<BookManager, String> {
+        match self.locale.as_deref() {
+            None | Some("en") => Ok(BookManager::default()),
+            Some(path) => {
+                let data = std::fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path, e))?;
+                let data: BookWithAbbreviationsList = serde_json::from_str(&data).map_err(|e| format!("Could not parse '{}': {}", path, e))?;
+                BookManager::new(data)
+            }
+        }
+    }
+
+    /// - The file in the first argument, or stdin when there is none (or it is `-`)
+    fn read_input(&self) -> Result<String, String> {
+        match self.args.first().map(String::as_str) {
+            None | Some("-") => {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input).map_err(|e| format!("Could not read stdin: {}", e))?;
+                Ok(input)
+            }
+            Some(path) => std::fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path, e)),
+        }
+    }
+}
+
+/// - The 1-based line and column (in characters) of a byte offset
+fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+fn format_passage(manager: &BookManager, style: CitationStyle, passage: &BookPassageSegments) -> Result<String, String> {
+    style.format_passage(manager, &passage.normalize()?)
+}
+
+fn passage_json(manager: &BookManager, style: CitationStyle, passage: &BookPassageSegments) -> Value {
+    let mut value = json!({
+        "book": passage.book,
+        "book_name": manager.book_id_to_name.get(&passage.book),
+        "segments": passage.segments,
+    });
+    match format_passage(manager, style, passage) {
+        Ok(label) => {
+            value["label"] = json!(label);
+            value["valid"] = json!(true);
+        }
+        Err(e) => {
+            value["valid"] = json!(false);
+            value["error"] = json!(e);
+        }
+    }
+    value
+}
+
+/// - An invalid reference exits with 1 in every format, like `validate`
+fn parse(options: &Options, manager: &BookManager) -> Result<ExitCode, String> {
+    let input = options.args.join(" ");
+    let Some(passage) = manager.parse_reference(&input) else {
+        eprintln!("error: Could not find a reference in '{}'", input);
+        return Ok(ExitCode::FAILURE);
+    };
+    let label = format_passage(manager, options.style, &passage);
+    match (options.format, &label) {
+        (Format::Json, _) => println!("{}", serde_json::to_string_pretty(&passage_json(manager, options.style, &passage)).unwrap()),
+        (_, Ok(label)) => println!("{}", label),
+        (_, Err(e)) => eprintln!("error: {}", e),
+    }
+    Ok(if label.is_ok() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn extract(options: &Options, manager: &BookManager) -> Result<ExitCode, String> {
+    let input = options.read_input()?;
+    let references: Vec<(ReferenceMatch, (usize, usize))> = manager.extract_references(&input).into_iter()
+        .map(|reference| {
+            let position = line_column(&input, reference.span.start);
+            (reference, position)
+        })
+        .collect();
+    match options.format {
+        Format::Json => {
+            let values: Vec<Value> = references.iter().map(|(reference, (line, column))| {
+                let mut value = passage_json(manager, options.style, &reference.passage);
+                value["line"] = json!(line);
+                value["column"] = json!(column);
+                value["text"] = json!(reference.text);
+                value
+            }).collect();
+            println!("{}", serde_json::to_string_pretty(&values).unwrap());
+        }
+        _ => for (reference, (line, column)) in references.iter() {
+            let label = format_passage(manager, options.style, &reference.passage).unwrap_or_else(|e| format!("invalid: {}", e));
+            println!("{}:{}\t{}\t{}", line, column, reference.text, label);
+        },
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// - Invalid references are kept as they were written, and everything else is kept byte for byte
+fn normalized(options: &Options, manager: &BookManager, input: &str) -> String {
+    manager.rewrite(input, |reference| {
+        format_passage(manager, options.style, &reference.passage).unwrap_or_else(|_| reference.text.to_string())
+    })
+}
+
+fn normalize(options: &Options, manager: &BookManager) -> Result<ExitCode, String> {
+    let input = options.read_input()?;
+    let output = normalized(options, manager, &input);
+    match options.format {
+        Format::Json => println!("{}", json!({ "text": output })),
+        _ => print!("{}", output),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn validate(options: &Options, manager: &BookManager) -> Result<ExitCode, String> {
+    let results: Vec<(String, Result<String, String>)> = if options.args.is_empty() {
+        let input = options.read_input()?;
+        manager.extract_references(&input).into_iter()
+            .map(|reference| (reference.text.to_string(), format_passage(manager, options.style, &reference.passage)))
+            .collect()
+    } else {
+        options.args.iter().map(|input| {
+            let result = manager.parse_reference(input)
+                .ok_or_else(|| String::from("Could not find a reference"))
+                .and_then(|passage| format_passage(manager, options.style, &passage));
+            (input.clone(), result)
+        }).collect()
+    };
+    match options.format {
+        Format::Json => {
+            let values: Vec<Value> = results.iter().map(|(input, result)| match result {
+                Ok(label) => json!({ "input": input, "valid": true, "label": label }),
+                Err(e) => json!({ "input": input, "valid": false, "error": e }),
+            }).collect();
+            println!("{}", serde_json::to_string_pretty(&values).unwrap());
+        }
+        _ => for (input, result) in results.iter() {
+            match result {
+                Ok(label) => println!("valid\t{}\t{}", input, label),
+                Err(e) => println!("invalid\t{}\t{}", input, e),
+            }
+        },
+    }
+    Ok(if results.iter().all(|(_, result)| result.is_ok()) { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn show(options: &Options, manager: &BookManager) -> Result<ExitCode, String> {
+    let input = options.args.join(" ");
+    let passage = manager.parse_reference(&input).ok_or_else(|| format!("Could not find a reference in '{}'", input))?;
+    let passage = passage.normalize()?;
+    let label = options.style.format_passage(manager, &passage)?;
+    let path = options.bible.as_deref().ok_or("`show` needs a Bible file: --bible <FILE>")?;
+    let bible = BibleVerseOrganizer::load(manager, path)?;
+
+    if passage.iter().all(|seg| bible.iter_segment_content(&seg).next().is_none()) {
+        eprintln!("error: '{}' has no text for {}", path, label);
+        return Ok(ExitCode::FAILURE);
+    }
+
+    if options.format == Format::Json {
+        let verses: Vec<Value> = passage.iter().flat_map(|seg| {
+            bible.iter_segment_content(&seg).map(|psg| json!({
+                "book": psg.book,
+                "chapter": psg.segment.chapter,
+                "verse": psg.segment.verse,
+                "text": psg.content,
+            })).collect::<Vec<_>>()
+        }).collect();
+        println!("{}", serde_json::to_string_pretty(&json!({ "reference": label, "verses": verses })).unwrap());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut formatter = match options.format {
+        Format::Numbered => PassageFormatter::default(),
+        Format::Markdown => PassageFormatter::new("{content}", "{content}", "{content}", "<sup>{verse}</sup> {content}")?
+            .with_joiners(" ", " ", "\n\n"),
+        _ => PassageFormatter::new("{content}", "{content}", "{content}", "{content}")?
+            .with_joiners(" ", " ", "\n"),
+    };
+    if let Some(template) = &options.template {
+        formatter.verse = template.clone();
+    }
+    let text = passage.render(manager, &bible, &formatter)?;
+    match options.format {
+        Format::Markdown => {
+            for line in text.lines() {
+                println!("{}", if line.is_empty() { String::from(">") } else { format!("> {}", line) });
+            }
+            println!(">\n> — {}", label);
+        }
+        _ => println!("{}", text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run(options: &Options) -> Result<ExitCode, String> {
+    if options.command.is_empty() || options.command == "help" {
+        println!("{}", USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+    if options.command != "show" && matches!(options.format, Format::Numbered | Format::Markdown) {
+        Err(format!("`{}` only has --format json or text", options.command))?
+    }
+    if options.command != "show" && (options.bible.is_some() || options.template.is_some()) {
+        Err(format!("--bible and --template are only for `show`, not `{}`", options.command))?
+    }
+    let manager = options.book_manager()?;
+    match options.command.as_str() {
+        "parse" => parse(options, &manager),
+        "extract" => extract(options, &manager),
+        "normalize" => normalize(options, &manager),
+        "validate" => validate(options, &manager),
+        "show" => show(options, &manager),
+        other => Err(format!("Unknown command '{}'\n\n{}", other, USAGE)),
+    }
+}
+
+fn main() -> ExitCode {
+    let result = Options::parse(std::env::args().skip(1)).and_then(|options| run(&options));
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::from(2)
+    })
+}
+
+#[cfg(test)]
+mod bible_ref_tests {
+    use bible_reference_parser::citation::CitationStyle;
+
+    use std::process::ExitCode;
+
+    use bible_reference_parser::book_manager::BookManager;
+
+    use super::{line_column, normalized, parse, run, Format, Options};
+
+    fn options(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn arguments() {
+        let parsed = options(&["parse", "--style", "sbl", "John", "3:16", "--format=json"]).unwrap();
+        assert_eq!(parsed.command, "parse");
+        assert_eq!(parsed.style, CitationStyle::SBL);
+        assert_eq!(parsed.format, Format::Json);
+        assert_eq!(parsed.args, vec!["John", "3:16"]);
+
+        assert!(options(&["parse", "--style"]).is_err());
+        assert!(options(&["parse", "--format", "xml"]).is_err());
+        assert!(options(&["parse", "--unknown"]).is_err());
+
+        let parsed = options(&["show", "John 3:16", "--bible", "esv.json", "--format", "markdown", "--template", "{verse}. {content}"]).unwrap();
+        assert_eq!(parsed.bible.as_deref(), Some("esv.json"));
+        assert_eq!(parsed.format, Format::Markdown);
+        assert_eq!(parsed.template.unwrap().to_string(), "{verse}. {content}");
+        assert!(options(&["show", "--template", "{unknown}"]).is_err());
+
+        // the formats of passage text are only for `show`
+        for command in ["parse", "extract", "normalize", "validate"] {
+            assert!(run(&options(&[command, "--format", "numbered"]).unwrap()).is_err());
+            assert!(run(&options(&[command, "--format", "markdown"]).unwrap()).is_err());
+            assert!(run(&options(&[command, "--bible", "esv.json"]).unwrap()).is_err());
+            assert!(run(&options(&[command, "--template", "{verse}. {content}"]).unwrap()).is_err());
+        }
+    }
+
+    #[test]
+    fn exit_codes() {
+        let manager = BookManager::default();
+        for format in ["text", "json"] {
+            assert_eq!(parse(&options(&["parse", "John 3:16", "--format", format]).unwrap(), &manager), Ok(ExitCode::SUCCESS));
+            assert_eq!(parse(&options(&["parse", "John 30:1", "--format", format]).unwrap(), &manager), Ok(ExitCode::FAILURE));
+            assert_eq!(parse(&options(&["parse", "I am 300 years old", "--format", format]).unwrap(), &manager), Ok(ExitCode::FAILURE));
+        }
+    }
+
+    #[test]
+    fn normalize_keeps_prose() {
+        let manager = BookManager::default();
+        let options = options(&["normalize"]).unwrap();
+        for input in ["I am 5 years old, this is 6 inches.\n", "  so  many\tspaces —  and 300 people \r\n", "Mark my words: ex 12 was re 5.", ""] {
+            assert_eq!(normalized(&options, &manager, input), input);
+        }
+        assert_eq!(normalized(&options, &manager, "I am 5 years old. Read jn 3:16!\n"), "I am 5 years old. Read John 3:16!\n");
+    }
+
+    #[test]
+    fn positions() {
+        let input = "one\ntwo John 3:16\nthree";
+        assert_eq!(line_column(input, 0), (1, 1));
+        assert_eq!(line_column(input, input.find("John").unwrap()), (2, 5));
+        assert_eq!(line_column("é John", 3), (1, 3));
+    }
+}
@@ -0,0 +1,263 @@
+use std::{io::Read, ops::Range, path::Path, process::ExitCode};
+
+use bible_reference_parser::{
+    bible_verse_organizer::BibleVerseOrganizer,
+    book_manager::BookManager,
+    link::LinkBuilder,
+    passage::PassageFormatter,
+};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde_json::Value;
+
+const USAGE: &str = "\
+Usage: mdbook-bible [supports <RENDERER>]
+
+An mdBook preprocessor that links Bible references and expands `{{#bible John 3:16-18}}` into quoted text
+
+book.toml:
+  [preprocessor.bible]
+  bible = \"esv.json\"                   # verse text for {{#bible}}: a JSON object of `BBCCCVVV` verse ids to text, or lines like `John 3:16 For God ...`
+  link = \"/bible/{osis}\"               # the URL of each reference (default: /bible/{book_slug}/{chapter}), or `false` to not link them";
+
+/// - Links stay on the book's own site unless `link` is set
+const DEFAULT_LINK: &str = "/bible/{book_slug}/{chapter}";
+
+/// - `\{{#bible ...}}` is escaped, like the mdBook directives
+static DIRECTIVE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\\)?\{\{#bible\s+([^}]*)\}\}").unwrap());
+
+/// - Code blocks, inline code, links, and HTML tags, where references are left alone
+static PROTECTED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?ms)^ {0,3}(?:```|~~~).*?^ {0,3}(?:```|~~~)[^\n]*$|`[^`\n]+`|\[[^\]\n]*\]\([^)\n]*\)|<a\b.*?</a>|<[^>\n]+>").unwrap()
+});
+
+struct Preprocessor {
+    manager: BookManager,
+    link: Option<LinkBuilder>,
+    bible: Option<BibleVerseOrganizer<String>>,
+    quote: PassageFormatter,
+}
+
+impl Preprocessor {
+    /// - Reads `[preprocessor.bible]` from the mdBook context, with the Bible file relative to the book root
+    fn new(context: &Value) -> Result<Self, String> {
+        let manager = BookManager::default();
+        let config = &context["config"]["preprocessor"]["bible"];
+        let link = match &config["link"] {
+            Value::Bool(false) => None,
+            Value::String(template) => Some(LinkBuilder::new(template)?),
+            _ => Some(LinkBuilder::new(DEFAULT_LINK)?),
+        };
+        let bible = match config["bible"].as_str() {
+            Some(path) => {
+                let root = Path::new(context["root"].as_str().unwrap_or("."));
+                Some(BibleVerseOrganizer::load(&manager, root.join(path))?)
+            }
+            None => None,
+        };
+        let quote = PassageFormatter::new("> {content}\n>\n> — {book} {label}", "{content}", "{content}", "<sup>{verse}</sup> {content}")?
+            .with_joiners(" ", " ", "\n>\n> ");
+        Ok(Self { manager, link, bible, quote })
+    }
+
+    fn quote(&self, reference: &str) -> Result<String, String> {
+        let passage = self.manager.parse_reference(reference).ok_or_else(|| format!("Could not find a reference in '{}'", reference))?;
+        let passage = passage.normalize()?;
+        let bible = self.bible.as_ref().ok_or("Set `bible` in [preprocessor.bible] to quote passages")?;
+        if passage.iter().all(|seg| bible.iter_segment_content(&seg).next().is_none()) {
+            return Err(format!("There is no text for '{}'", reference.trim()));
+        }
+        passage.render(&self.manager, bible, &self.quote)
+    }
+
+    /// - Links every reference outside of code, links, and directives, then expands the directives
+    fn process(&self, content: &str, warnings: &mut Vec<String>) -> String {
+        let protected: Vec<Range<usize>> = PROTECTED.find_iter(content).chain(DIRECTIVE.find_iter(content))
+            .map(|found| found.range())
+            .collect();
+        let linked = match &self.link {
+            Some(link) => self.manager.rewrite(content, |reference| {
+                if protected.iter().any(|range| range.contains(&reference.span.start)) {
+                    return reference.text.to_string();
+                }
+                // in reading order, so `John 3:18, 16` does not link a backwards range
+                let url = reference.passage.normalize().and_then(|passage| link.passage_url(&self.manager, &passage));
+                match url {
+                    Ok(url) => format!("[{}]({})", reference.text, url),
+                    Err(e) => {
+                        warnings.push(format!("Invalid reference '{}': {}", reference.text, e));
+                        reference.text.to_string()
+                    }
+                }
+            }),
+            None => content.to_string(),
+        };
+        DIRECTIVE.replace_all(&linked, |captures: &Captures| {
+            if captures.get(1).is_some() {
+                return captures[0][1..].to_string();
+            }
+            self.quote(&captures[2]).unwrap_or_else(|e| {
+                warnings.push(e);
+                captures[0].to_string()
+            })
+        }).to_string()
+    }
+
+    /// - Processes every chapter in `sections` (and their sub-chapters), and leaves separators and part titles alone
+    fn process_sections(&self, sections: &mut Value, warnings: &mut Vec<String>) {
+        let Some(sections) = sections.as_array_mut() else {
+            return;
+        };
+        for section in sections {
+            let Some(chapter) = section.get_mut("Chapter").filter(|chapter| chapter.is_object()) else {
+                continue;
+            };
+            if let Some(content) = chapter["content"].as_str() {
+                let mut chapter_warnings = vec![];
+                chapter["content"] = Value::String(self.process(content, &mut chapter_warnings));
+                let name = chapter["name"].as_str().unwrap_or("");
+                warnings.extend(chapter_warnings.into_iter().map(|warning| format!("{}: {}", name, warning)));
+            }
+            self.process_sections(&mut chapter["sub_items"], warnings);
+        }
+    }
+}
+
+/// - Takes the `[context, book]` that mdBook sends, and returns the processed book
+fn run(input: &str, warnings: &mut Vec<String>) -> Result<Value, String> {
+    let input: Value = serde_json::from_str(input).map_err(|e| format!("Expected mdBook's [context, book] JSON: {}", e))?;
+    let (context, mut book) = match input {
+        Value::Array(mut items) if items.len() == 2 => {
+            let book = items.pop().unwrap();
+            (items.pop().unwrap(), book)
+        }
+        _ => Err("Expected mdBook's [context, book] JSON")?,
+    };
+    let preprocessor = Preprocessor::new(&context)?;
+    preprocessor.process_sections(&mut book["sections"], warnings);
+    Ok(book)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        // every renderer gets Markdown, so every renderer is supported
+        Some("supports") => return ExitCode::SUCCESS,
+        Some("-h" | "--help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Some(other) => {
+            eprintln!("error: Unknown argument '{}'\n\n{}", other, USAGE);
+            return ExitCode::from(2);
+        }
+        None => {}
+    }
+
+    let mut input = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("error: Could not read stdin: {}", e);
+        return ExitCode::FAILURE;
+    }
+    let mut warnings = vec![];
+    let result = run(&input, &mut warnings);
+    for warning in warnings {
+        eprintln!("[WARN] (mdbook-bible): {}", warning);
+    }
+    match result {
+        Ok(book) => {
+            println!("{}", book);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("[ERROR] (mdbook-bible): {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod mdbook_bible_tests {
+    use serde_json::{json, Value};
+
+    use super::run;
+
+    /// - Each test has its own directory, since tests run in parallel
+    fn book_input(test: &str, config: Value, content: &str) -> String {
+        let root = std::env::temp_dir().join(format!("mdbook-bible-tests-{}-{}", test, std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("bible.txt"), "John 3:16 For God so loved the world,\nJohn 3:17 For God did not send his Son\nJohn 4:1 Now when Jesus learned\n").unwrap();
+        json!([
+            { "root": root, "config": { "preprocessor": { "bible": config } }, "renderer": "html", "mdbook_version": "0.4.40" },
+            { "sections": [
+                { "Chapter": { "name": "One", "content": content, "number": [1], "sub_items": [
+                    { "Chapter": { "name": "Nested", "content": "See Rom 8:28.", "number": [1, 1], "sub_items": [], "path": "nested.md" } },
+                ], "path": "one.md" } },
+                "Separator",
+                { "PartTitle": "Part" },
+            ], "__non_exhaustive": null },
+        ]).to_string()
+    }
+
+    fn chapter(book: &Value) -> &str {
+        book["sections"][0]["Chapter"]["content"].as_str().unwrap()
+    }
+
+    #[test]
+    fn links() {
+        let mut warnings = vec![];
+        let content = "Read Jn 3:16, 18 and John 30:1.\n\n```\nJohn 3:16\n```\n\n`John 3:16` and [John 3:16](/already) stay.";
+        let book = run(&book_input("links", json!({ "link": "/bible/{osis}" }), content), &mut warnings).unwrap();
+        assert_eq!(
+            chapter(&book),
+            "Read [Jn 3:16, 18](/bible/John.3.16%2CJohn.3.18) and John 30:1.\n\n```\nJohn 3:16\n```\n\n`John 3:16` and [John 3:16](/already) stay."
+        );
+        assert_eq!(book["sections"][0]["Chapter"]["sub_items"][0]["Chapter"]["content"], "See [Rom 8:28](/bible/Rom.8.28).");
+        assert_eq!(book["sections"][1], "Separator");
+        assert_eq!(book["__non_exhaustive"], Value::Null);
+        assert_eq!(warnings, vec!["One: Invalid reference 'John 30:1': There is no 'Chapter 30' in 'Book 43'"]);
+
+        let book = run(&book_input("links", json!({ "link": false }), "Read John 3:16."), &mut vec![]).unwrap();
+        assert_eq!(chapter(&book), "Read John 3:16.");
+
+        let book = run(&book_input("links", json!({}), "Read John 3:16."), &mut vec![]).unwrap();
+        assert_eq!(chapter(&book), "Read [John 3:16](/bible/john/3).");
+    }
+
+    #[test]
+    fn prose_and_order() {
+        let mut warnings = vec![];
+        let content = "I am 300 years old, I am 5 years old, this is 6 inches. See John 3:18, 16.";
+        let link = "/bible/{osis}#{chapter}:{verse}-{end_chapter}:{end_verse}";
+        let book = run(&book_input("prose_and_order", json!({ "link": link }), content), &mut warnings).unwrap();
+        assert_eq!(chapter(&book), "I am 300 years old, I am 5 years old, this is 6 inches. See [John 3:18, 16](/bible/John.3.16%2CJohn.3.18#3:16-3:18).");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn quotes() {
+        let mut warnings = vec![];
+        let content = "{{#bible John 3:16-4:1}}\n\n\\{{#bible John 3:16}}\n\n{{#bible John 5:1}}";
+        let book = run(&book_input("quotes", json!({ "bible": "bible.txt", "link": false }), content), &mut warnings).unwrap();
+        assert_eq!(
+            chapter(&book),
+            "> <sup>16</sup> For God so loved the world, <sup>17</sup> For God did not send his Son <sup>1</sup> Now when Jesus learned\n>\n> — John 3:16-4:1\n\n{{#bible John 3:16}}\n\n{{#bible John 5:1}}"
+        );
+        assert_eq!(warnings, vec!["One: There is no text for 'John 5:1'"]);
+
+        // the reference inside the directive is not linked
+        let book = run(&book_input("quotes", json!({ "bible": "bible.txt", "link": "/{osis}" }), "{{#bible John 3:16}}"), &mut vec![]).unwrap();
+        assert_eq!(chapter(&book), "> <sup>16</sup> For God so loved the world,\n>\n> — John 3:16");
+
+        let mut warnings = vec![];
+        run(&book_input("quotes", json!({ "link": false }), "{{#bible John 3:16}}"), &mut warnings).unwrap();
+        assert_eq!(warnings, vec!["One: Set `bible` in [preprocessor.bible] to quote passages"]);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(run("{}", &mut vec![]).is_err());
+        assert!(run(&book_input("errors", json!({ "link": "/{content}" }), ""), &mut vec![]).is_err());
+        assert!(run(&book_input("errors", json!({ "bible": "missing.json" }), ""), &mut vec![]).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{book_chapter_verse::{chapter_count, verse_count, BOOK_CHAPTER_VERSE_COUNT}, book_manager::DEFAULT_BOOK_MANAGER, canon::BookInfo};
+
+/// - A book of the Bible, so ids can be checked once instead of passing around bare `u8`s
+/// - Converts to and from the 1-indexed ids used everywhere else (`Genesis = 1`, `Revelation = 66`)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Book {
+    Genesis = 1,
+    Exodus,
+    Leviticus,
+    Numbers,
+    Deuteronomy,
+    Joshua,
+    Judges,
+    Ruth,
+    FirstSamuel,
+    SecondSamuel,
+    FirstKings,
+    SecondKings,
+    FirstChronicles,
+    SecondChronicles,
+    Ezra,
+    Nehemiah,
+    Esther,
+    Job,
+    Psalms,
+    Proverbs,
+    Ecclesiastes,
+    SongOfSolomon,
+    Isaiah,
+    Jeremiah,
+    Lamentations,
+    Ezekiel,
+    Daniel,
+    Hosea,
+    Joel,
+    Amos,
+    Obadiah,
+    Jonah,
+    Micah,
+    Nahum,
+    Habakkuk,
+    Zephaniah,
+    Haggai,
+    Zechariah,
+    Malachi,
+    Matthew,
+    Mark,
+    Luke,
+    John,
+    Acts,
+    Romans,
+    FirstCorinthians,
+    SecondCorinthians,
+    Galatians,
+    Ephesians,
+    Philippians,
+    Colossians,
+    FirstThessalonians,
+    SecondThessalonians,
+    FirstTimothy,
+    SecondTimothy,
+    Titus,
+    Philemon,
+    Hebrews,
+    James,
+    FirstPeter,
+    SecondPeter,
+    FirstJohn,
+    SecondJohn,
+    ThirdJohn,
+    Jude,
+    Revelation,
+}
+
+impl Book {
+    /// - Every book in canon order
+    pub const ALL: [Book; 66] = [
+        Book::Genesis,
+        Book::Exodus,
+        Book::Leviticus,
+        Book::Numbers,
+        Book::Deuteronomy,
+        Book::Joshua,
+        Book::Judges,
+        Book::Ruth,
+        Book::FirstSamuel,
+        Book::SecondSamuel,
+        Book::FirstKings,
+        Book::SecondKings,
+        Book::FirstChronicles,
+        Book::SecondChronicles,
+        Book::Ezra,
+        Book::Nehemiah,
+        Book::Esther,
+        Book::Job,
+        Book::Psalms,
+        Book::Proverbs,
+        Book::Ecclesiastes,
+        Book::SongOfSolomon,
+        Book::Isaiah,
+        Book::Jeremiah,
+        Book::Lamentations,
+        Book::Ezekiel,
+        Book::Daniel,
+        Book::Hosea,
+        Book::Joel,
+        Book::Amos,
+        Book::Obadiah,
+        Book::Jonah,
+        Book::Micah,
+        Book::Nahum,
+        Book::Habakkuk,
+        Book::Zephaniah,
+        Book::Haggai,
+        Book::Zechariah,
+        Book::Malachi,
+        Book::Matthew,
+        Book::Mark,
+        Book::Luke,
+        Book::John,
+        Book::Acts,
+        Book::Romans,
+        Book::FirstCorinthians,
+        Book::SecondCorinthians,
+        Book::Galatians,
+        Book::Ephesians,
+        Book::Philippians,
+        Book::Colossians,
+        Book::FirstThessalonians,
+        Book::SecondThessalonians,
+        Book::FirstTimothy,
+        Book::SecondTimothy,
+        Book::Titus,
+        Book::Philemon,
+        Book::Hebrews,
+        Book::James,
+        Book::FirstPeter,
+        Book::SecondPeter,
+        Book::FirstJohn,
+        Book::SecondJohn,
+        Book::ThirdJohn,
+        Book::Jude,
+        Book::Revelation,
+    ];
+
+    /// - Every book in canon order
+    pub fn iter() -> impl Iterator<Item = Book> {
+        Self::ALL.into_iter()
+    }
+
+    /// - The 1-indexed id (`Genesis = 1`)
+    pub fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn chapter_count(&self) -> u8 {
+        chapter_count(self.id()).expect("Every book has chapters")
+    }
+
+    /// - Every verse in every chapter of this book
+    pub fn verse_count(&self) -> u16 {
+        BOOK_CHAPTER_VERSE_COUNT[(self.id() - 1) as usize].iter().sum()
+    }
+
+    /// - Returns `None` if the chapter is not in this book
+    /// - Chapters start at 1
+    pub fn chapter_verse_count(&self, chapter: u8) -> Option<u8> {
+        verse_count(self.id(), chapter)
+    }
+
+    /// - See [`BookInfo`]
+    pub fn info(&self) -> BookInfo {
+        BookInfo::get(self.id()).expect("Every book has metadata")
+    }
+
+    /// - The display name from the default [`BookManager`](crate::book_manager::BookManager)
+    pub fn name(&self) -> &'static str {
+        DEFAULT_BOOK_MANAGER.book_id_to_name.get(&self.id()).expect("Every book has a name")
+    }
+
+    /// - The display abbreviation from the default [`BookManager`](crate::book_manager::BookManager)
+    pub fn abbreviation(&self) -> &'static str {
+        DEFAULT_BOOK_MANAGER.book_id_to_abbreviation.get(&self.id()).expect("Every book has an abbreviation")
+    }
+}
+
+impl TryFrom<u8> for Book {
+    type Error = String;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        let idx = (id as usize).checked_sub(1);
+        idx.and_then(|idx| Self::ALL.get(idx).copied())
+            .ok_or_else(|| format!("There is no 'Book {}' in the Bible", id))
+    }
+}
+
+impl From<Book> for u8 {
+    fn from(book: Book) -> Self {
+        book.id()
+    }
+}
+
+impl Display for Book {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// - Accepts the name or any abbreviation known to the default [`BookManager`](crate::book_manager::BookManager)
+/// - Ex: `"John"`, `"jn"`, `"Jn."`
+impl FromStr for Book {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = DEFAULT_BOOK_MANAGER.book_id(s)
+            .ok_or_else(|| format!("'{}' is not a known book", s))?;
+        Book::try_from(id)
+    }
+}
+
+#[cfg(test)]
+mod book_tests {
+    use crate::{book_segment::BookSegment, canon::Testament};
+
+    use super::Book;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Book::try_from(1), Ok(Book::Genesis));
+        assert_eq!(Book::try_from(43), Ok(Book::John));
+        assert_eq!(Book::try_from(66), Ok(Book::Revelation));
+        assert!(Book::try_from(0).is_err());
+        assert!(Book::try_from(67).is_err());
+
+        assert_eq!(u8::from(Book::FirstCorinthians), 46);
+        assert_eq!(BookSegment::chapter_verse(Book::John.into(), 3, 16), BookSegment::chapter_verse(43, 3, 16));
+
+        for (idx, book) in Book::iter().enumerate() {
+            assert_eq!(book.id() as usize, idx + 1);
+            assert_eq!(Book::try_from(book.id()), Ok(book));
+        }
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(Book::Genesis.chapter_count(), 50);
+        assert_eq!(Book::Genesis.verse_count(), 1533);
+        assert_eq!(Book::Jude.chapter_count(), 1);
+        assert_eq!(Book::John.chapter_verse_count(3), Some(36));
+        assert_eq!(Book::John.chapter_verse_count(22), None);
+        assert_eq!(Book::iter().map(|book| book.verse_count() as u32).sum::<u32>(), 31_102);
+        assert_eq!(Book::Matthew.info().testament, Testament::New);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Book::SongOfSolomon.to_string(), "Song of Solomon");
+        assert_eq!(Book::FirstJohn.to_string(), "1 John");
+        assert_eq!(Book::Genesis.abbreviation(), "Gn");
+
+        assert_eq!("John".parse(), Ok(Book::John));
+        assert_eq!("jn.".parse(), Ok(Book::John));
+        assert_eq!("1 Corinthians".parse(), Ok(Book::FirstCorinthians));
+        assert!("Maccabees".parse::<Book>().is_err());
+
+        for book in Book::iter() {
+            assert_eq!(book.to_string().parse(), Ok(book));
+        }
+    }
+}
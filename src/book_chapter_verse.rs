@@ -3,7 +3,7 @@ use std::ops::{Bound, RangeBounds};
 use derive_more::{Deref, DerefMut, From};
 use itertools::Itertools;
 
-use crate::{book_segment::BookSegment, passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse}};
+use crate::{book_segment::BookSegment, passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse, full_chapter::FullChapter}};
 
 // #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 // pub struct BookChapterVerse {
@@ -12,7 +12,8 @@ use crate::{book_segment::BookSegment, passage_segments::{chapter_range::Chapter
 //     verse: u8,
 // }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// - Ordered by book, then chapter, then verse (the same as [`BookChapterVerse::as_verse`])
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[derive(From, Deref, DerefMut)]
 pub struct BookChapterVerse(BookSegment<ChapterVerse>);
 
@@ -99,6 +100,115 @@ pub const BOOK_CHAPTER_VERSE_COUNT: &[&[u16]] = &[
     &[20, 29, 22, 11, 14, 17, 17, 13, 21, 11, 19, 17, 18, 20, 8, 21, 18, 24, 21, 15, 27, 21]
 ];
 
+pub const LAST_CHAPTER: u16 = 1_189;
+
+/// - Genesis has 0 chapters before it
+/// - Exodus has 50 chapters before it
+pub const CHAPTERS_BEFORE_BOOK: [u16; 66] = chapters_before_book();
+
+/// - This is indexed by the global chapter id - 1 (see [`chapter_id`])
+/// - Genesis 1 has 0 verses before it
+/// - Genesis 2 has 31 verses before it
+pub const VERSES_BEFORE_CHAPTER: [u16; LAST_CHAPTER as usize] = verses_before_chapter();
+
+/// - This is indexed by the global verse id - 1, and holds the global chapter id - 1
+static CHAPTER_IDX_OF_VERSE: [u16; LAST_VERSE as usize] = chapter_idx_of_verse();
+
+/// - This is indexed by the global chapter id - 1, and holds the book - 1
+static BOOK_IDX_OF_CHAPTER: [u8; LAST_CHAPTER as usize] = book_idx_of_chapter();
+
+// These tables are built at compile time from `BOOK_CHAPTER_VERSE_COUNT`,
+// so converting between global ids and book/chapter/verse never has to search
+
+const fn chapters_before_book() -> [u16; 66] {
+    let mut table = [0; 66];
+    let mut book_idx = 1;
+    while book_idx < 66 {
+        table[book_idx] = table[book_idx - 1] + BOOK_CHAPTER_VERSE_COUNT[book_idx - 1].len() as u16;
+        book_idx += 1;
+    }
+    table
+}
+
+const fn verses_before_chapter() -> [u16; LAST_CHAPTER as usize] {
+    let mut table = [0; LAST_CHAPTER as usize];
+    let (mut book_idx, mut chapter_idx, mut verses) = (0, 0, 0);
+    while book_idx < 66 {
+        let chapters = BOOK_CHAPTER_VERSE_COUNT[book_idx];
+        let mut idx = 0;
+        while idx < chapters.len() {
+            table[chapter_idx] = verses;
+            verses += chapters[idx];
+            chapter_idx += 1;
+            idx += 1;
+        }
+        book_idx += 1;
+    }
+    table
+}
+
+const fn chapter_idx_of_verse() -> [u16; LAST_VERSE as usize] {
+    let mut table = [0; LAST_VERSE as usize];
+    let mut chapter_idx = 0;
+    while chapter_idx < LAST_CHAPTER as usize {
+        let start = VERSES_BEFORE_CHAPTER[chapter_idx] as usize;
+        let end = if chapter_idx + 1 < LAST_CHAPTER as usize { VERSES_BEFORE_CHAPTER[chapter_idx + 1] as usize } else { LAST_VERSE as usize };
+        let mut verse_idx = start;
+        while verse_idx < end {
+            table[verse_idx] = chapter_idx as u16;
+            verse_idx += 1;
+        }
+        chapter_idx += 1;
+    }
+    table
+}
+
+const fn book_idx_of_chapter() -> [u8; LAST_CHAPTER as usize] {
+    let mut table = [0; LAST_CHAPTER as usize];
+    let mut book_idx = 0;
+    while book_idx < 66 {
+        let start = CHAPTERS_BEFORE_BOOK[book_idx] as usize;
+        let mut chapter_idx = start;
+        while chapter_idx < start + BOOK_CHAPTER_VERSE_COUNT[book_idx].len() {
+            table[chapter_idx] = book_idx as u8;
+            chapter_idx += 1;
+        }
+        book_idx += 1;
+    }
+    table
+}
+
+/// - Returns the global chapter id, from 1 (Genesis 1) to [`LAST_CHAPTER`] (Revelation 22)
+/// - Books and chapters start at 1
+pub fn chapter_id(book: u8, chapter: u8) -> Option<u16> {
+    let book_idx = (book as usize).checked_sub(1)?;
+    if chapter == 0 || chapter > chapter_count(book)? { return None; }
+    Some(CHAPTERS_BEFORE_BOOK[book_idx] + chapter as u16)
+}
+
+/// - The inverse of [`chapter_id`]
+pub fn from_chapter_id(id: u16) -> Option<BookSegment<FullChapter>> {
+    let chapter_idx = (id as usize).checked_sub(1)?;
+    let book_idx = *BOOK_IDX_OF_CHAPTER.get(chapter_idx)? as usize;
+    let chapter = (id - CHAPTERS_BEFORE_BOOK[book_idx]) as u8;
+    Some(BookSegment::full_chapter((book_idx + 1) as u8, chapter))
+}
+
+/// - Returns the number of chapters in a book
+/// - Books start at 1
+pub fn chapter_count(book: u8) -> Option<u8> {
+    let book_idx = (book as usize).checked_sub(1)?;
+    BOOK_CHAPTER_VERSE_COUNT.get(book_idx).map(|chapters| chapters.len() as u8)
+}
+
+/// - Returns the number of verses in a chapter of a book
+/// - Books and chapters start at 1
+pub fn verse_count(book: u8, chapter: u8) -> Option<u8> {
+    let book_idx = (book as usize).checked_sub(1)?;
+    let chapter_idx = (chapter as usize).checked_sub(1)?;
+    BOOK_CHAPTER_VERSE_COUNT.get(book_idx)?.get(chapter_idx).map(|&count| count as u8)
+}
+
 impl BookChapterVerse {
 
     fn book_idx(&self) -> usize { (self.book - 1) as usize }
@@ -128,55 +238,36 @@ impl BookChapterVerse {
     }
 
     /// - This method validates the book/chapter/verse numbers
-    pub fn from_verse(mut id: u16) -> Result<Self, String> {
-        if id == 0 {
-            return Err(format!("There is not 'Book {}' in the Bible", id));
-        }
-        id -= 1;
-        let verses_before_book_idx = VERSES_BEFORE_BOOK.binary_search(&id);
-        let book_idx = match verses_before_book_idx {
-            Ok(idx) => idx,
-            Err(idx) => {
-                if idx >= VERSES_BEFORE_BOOK.len() {
-                    return Err(format!("There is not 'Book {}' in the Bible", idx));
-                }
-                idx - 1
-            },
-        };
-        let verses_before_book = VERSES_BEFORE_BOOK[book_idx];
-
-        let chapter_verse_counts = BOOK_CHAPTER_VERSE_COUNT[book_idx];
-        let mut remaining = id - verses_before_book;
-
-        let mut chapter = 1;
-        let mut iter = chapter_verse_counts.iter();
-        while let Some(&verses_in_chapter) = iter.next() {
-            if verses_in_chapter > remaining {
-                break;
-            }
-            remaining -= verses_in_chapter;
-            chapter += 1;
+    /// - This runs in constant time (see [`VERSES_BEFORE_CHAPTER`])
+    pub fn from_verse(id: u16) -> Result<Self, String> {
+        if id == 0 || id > LAST_VERSE {
+            return Err(format!("There is not 'Verse {}' in the Bible", id));
         }
+        let chapter_idx = CHAPTER_IDX_OF_VERSE[(id - 1) as usize];
+        let book_idx = BOOK_IDX_OF_CHAPTER[chapter_idx as usize] as usize;
 
-        // verses start at 1
-        let verse = (remaining + 1) as u8;
-        
-        // Genesis is book 1 not 0
+        // everything starts at 1
         let book = (book_idx + 1) as u8;
+        let chapter = (chapter_idx + 1 - CHAPTERS_BEFORE_BOOK[book_idx]) as u8;
+        let verse = (id - VERSES_BEFORE_CHAPTER[chapter_idx as usize]) as u8;
 
-        BookChapterVerse::new(book, chapter, verse)
+        Ok(Self(BookSegment::chapter_verse(book, chapter, verse)))
     }
 
-    /// This will crash if BookChapterVerseId does not hold a valid verse
+    /// - This will crash if BookChapterVerseId does not hold a valid verse
+    /// - This runs in constant time (see [`VERSES_BEFORE_CHAPTER`])
     pub fn as_verse(&self) -> u16 {
-        let verses_before_book = VERSES_BEFORE_BOOK[self.book_idx()];
-
-        let book_chapters = BOOK_CHAPTER_VERSE_COUNT[self.book_idx()];
-        let verses_before_chapter: u16 = book_chapters.iter().take(self.chapter_idx()).sum();
+        VERSES_BEFORE_CHAPTER[self.chapter_id_idx()] + self.verse() as u16
+    }
 
-        let verses_before_verse = self.verse() as u16;
+    /// - The global chapter id of this verse (see [`chapter_id`])
+    /// - This will crash if BookChapterVerseId does not hold a valid verse
+    pub fn as_chapter(&self) -> u16 {
+        self.chapter_id_idx() as u16 + 1
+    }
 
-        verses_before_book + verses_before_chapter + verses_before_verse
+    fn chapter_id_idx(&self) -> usize {
+        CHAPTERS_BEFORE_BOOK[self.book_idx()] as usize + self.chapter_idx()
     }
 
     /// - This method indirectly validates the book/chapter/verse numbers
@@ -230,11 +321,93 @@ impl BookChapterVerse {
     }
 }
 
+// Navigation
+// - These cross chapter and book boundaries
+// - They saturate at Genesis 1:1 and Revelation 22:21
+impl BookChapterVerse {
+    pub fn first() -> Self {
+        Self(BookSegment::chapter_verse(1, 1, 1))
+    }
+
+    pub fn last() -> Self {
+        Self(BookSegment::chapter_verse(66, 22, 21))
+    }
+
+    /// - This method validates the book number
+    pub fn last_of_book(book: u8) -> Result<Self, String> {
+        let chapter = chapter_count(book).ok_or_else(|| format!("There is no 'Book {}' in the Bible", book))?;
+        Self::last_of_chapter(book, chapter)
+    }
+
+    /// - This method validates the book/chapter numbers
+    pub fn last_of_chapter(book: u8, chapter: u8) -> Result<Self, String> {
+        let verse = verse_count(book, chapter).ok_or_else(|| format!("There is no 'Chapter {}' in 'Book {}'", chapter, book))?;
+        Self::new(book, chapter, verse)
+    }
+
+    /// - Moves `n` verses forward (or backward when negative)
+    pub fn offset(&self, n: i32) -> Self {
+        let id = (self.as_verse() as i32 + n).clamp(1, LAST_VERSE as i32);
+        Self::from_verse(id as u16).expect("The verse id was clamped to a valid verse")
+    }
+
+    /// - How many verses apart these are, across chapters and books
+    pub fn verse_distance(&self, other: &BookChapterVerse) -> u16 {
+        self.as_verse().abs_diff(other.as_verse())
+    }
+
+    pub fn next_verse(&self) -> Self {
+        self.offset(1)
+    }
+
+    pub fn prev_verse(&self) -> Self {
+        self.offset(-1)
+    }
+
+    /// - The first verse of the next chapter
+    pub fn next_chapter(&self) -> Self {
+        if self.remaining_chapters().is_some() {
+            Self(BookSegment::chapter_verse(self.book, self.chapter() + 1, 1))
+        } else if self.book < 66 {
+            Self(BookSegment::chapter_verse(self.book + 1, 1, 1))
+        } else {
+            Self::last()
+        }
+    }
+
+    /// - The first verse of the previous chapter
+    pub fn prev_chapter(&self) -> Self {
+        if self.chapter() > 1 {
+            Self(BookSegment::chapter_verse(self.book, self.chapter() - 1, 1))
+        } else if self.book > 1 {
+            let chapter = chapter_count(self.book - 1).expect("Every book has chapters");
+            Self(BookSegment::chapter_verse(self.book - 1, chapter, 1))
+        } else {
+            Self::first()
+        }
+    }
+}
+
 #[cfg(test)]
 mod book_chapter_verse_tests {
     use itertools::Itertools;
 
-    use super::BookChapterVerse;
+    use crate::book_segment::BookSegment;
+
+    use super::{chapter_count, chapter_id, from_chapter_id, verse_count, BookChapterVerse, BOOK_CHAPTER_VERSE_COUNT, LAST_CHAPTER, LAST_VERSE, VERSES_BEFORE_BOOK};
+
+    #[test]
+    fn counts() {
+        assert_eq!(chapter_count(0), None);
+        assert_eq!(chapter_count(1), Some(50));
+        assert_eq!(chapter_count(65), Some(1));
+        assert_eq!(chapter_count(67), None);
+
+        assert_eq!(verse_count(1, 0), None);
+        assert_eq!(verse_count(1, 1), Some(31));
+        assert_eq!(verse_count(19, 119), Some(176));
+        assert_eq!(verse_count(1, 51), None);
+    }
 
     #[test]
     fn new() -> Result<(), String> {
@@ -285,6 +458,14 @@ mod book_chapter_verse_tests {
             BookChapterVerse::new(40, 2, 1)?
         );
 
+        assert_eq!(
+            BookChapterVerse::from_verse(31102)?,
+            BookChapterVerse::new(66, 22, 21)?
+        );
+
+        assert!(BookChapterVerse::from_verse(0).is_err());
+        assert!(BookChapterVerse::from_verse(31103).is_err());
+
         Ok(())
     }
 
@@ -427,4 +608,110 @@ mod book_chapter_verse_tests {
 
         Ok(())
     }
+
+    #[test]
+    fn navigate_verses() -> Result<(), String> {
+        assert_eq!(BookChapterVerse::new(1, 1, 1)?.next_verse(), BookChapterVerse::new(1, 1, 2)?);
+        assert_eq!(BookChapterVerse::new(1, 1, 31)?.next_verse(), BookChapterVerse::new(1, 2, 1)?);
+        assert_eq!(BookChapterVerse::new(1, 50, 26)?.next_verse(), BookChapterVerse::new(2, 1, 1)?);
+        assert_eq!(BookChapterVerse::new(66, 22, 21)?.next_verse(), BookChapterVerse::new(66, 22, 21)?);
+
+        assert_eq!(BookChapterVerse::new(1, 1, 2)?.prev_verse(), BookChapterVerse::new(1, 1, 1)?);
+        assert_eq!(BookChapterVerse::new(1, 2, 1)?.prev_verse(), BookChapterVerse::new(1, 1, 31)?);
+        assert_eq!(BookChapterVerse::new(2, 1, 1)?.prev_verse(), BookChapterVerse::new(1, 50, 26)?);
+        assert_eq!(BookChapterVerse::new(1, 1, 1)?.prev_verse(), BookChapterVerse::new(1, 1, 1)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn navigate_chapters() -> Result<(), String> {
+        assert_eq!(BookChapterVerse::new(1, 1, 5)?.next_chapter(), BookChapterVerse::new(1, 2, 1)?);
+        assert_eq!(BookChapterVerse::new(1, 50, 5)?.next_chapter(), BookChapterVerse::new(2, 1, 1)?);
+        assert_eq!(BookChapterVerse::new(66, 22, 5)?.next_chapter(), BookChapterVerse::new(66, 22, 21)?);
+
+        assert_eq!(BookChapterVerse::new(1, 2, 5)?.prev_chapter(), BookChapterVerse::new(1, 1, 1)?);
+        assert_eq!(BookChapterVerse::new(2, 1, 5)?.prev_chapter(), BookChapterVerse::new(1, 50, 1)?);
+        assert_eq!(BookChapterVerse::new(1, 1, 5)?.prev_chapter(), BookChapterVerse::new(1, 1, 1)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn offset() -> Result<(), String> {
+        let verse = BookChapterVerse::new(43, 3, 16)?;
+        assert_eq!(verse.offset(0), verse);
+        assert_eq!(verse.offset(2), BookChapterVerse::new(43, 3, 18)?);
+        assert_eq!(verse.offset(-15), BookChapterVerse::new(43, 3, 1)?);
+        assert_eq!(verse.offset(-16), BookChapterVerse::new(43, 2, 25)?);
+        assert_eq!(verse.offset(100_000), BookChapterVerse::last());
+        assert_eq!(verse.offset(-100_000), BookChapterVerse::first());
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_every_verse() -> Result<(), String> {
+        let mut expected_id = 0;
+        for (book_idx, chapters) in BOOK_CHAPTER_VERSE_COUNT.iter().enumerate() {
+            let book = (book_idx + 1) as u8;
+            assert_eq!(BookChapterVerse::new(book, 1, 1)?.as_verse(), VERSES_BEFORE_BOOK[book_idx] + 1);
+            for (chapter_idx, &verses) in chapters.iter().enumerate() {
+                let chapter = (chapter_idx + 1) as u8;
+                for verse in 1..=verses as u8 {
+                    expected_id += 1;
+                    let bcv = BookChapterVerse::new(book, chapter, verse)?;
+                    assert_eq!(bcv.as_verse(), expected_id);
+                    assert_eq!(BookChapterVerse::from_verse(expected_id)?, bcv);
+                }
+            }
+        }
+        assert_eq!(expected_id, LAST_VERSE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chapter_ids() -> Result<(), String> {
+        assert_eq!(chapter_id(1, 1), Some(1));
+        assert_eq!(chapter_id(2, 1), Some(51));
+        assert_eq!(chapter_id(66, 22), Some(LAST_CHAPTER));
+        assert_eq!(chapter_id(1, 51), None);
+        assert_eq!(chapter_id(1, 0), None);
+        assert_eq!(chapter_id(0, 1), None);
+        assert_eq!(chapter_id(67, 1), None);
+
+        assert_eq!(from_chapter_id(51), Some(BookSegment::full_chapter(2, 1)));
+        assert_eq!(from_chapter_id(0), None);
+        assert_eq!(from_chapter_id(LAST_CHAPTER + 1), None);
+
+        assert_eq!(BookChapterVerse::new(43, 3, 16)?.as_chapter(), chapter_id(43, 3).unwrap());
+
+        let mut expected_id = 0;
+        for book in 1..=66 {
+            for chapter in 1..=chapter_count(book).unwrap() {
+                expected_id += 1;
+                assert_eq!(chapter_id(book, chapter), Some(expected_id));
+                assert_eq!(from_chapter_id(expected_id), Some(BookSegment::full_chapter(book, chapter)));
+            }
+        }
+        assert_eq!(expected_id, LAST_CHAPTER);
+
+        Ok(())
+    }
+
+    #[test]
+    fn distance_and_order() -> Result<(), String> {
+        let verse = BookChapterVerse::new(43, 3, 16)?;
+        assert_eq!(verse.verse_distance(&BookChapterVerse::new(43, 3, 18)?), 2);
+        assert_eq!(verse.verse_distance(&BookChapterVerse::new(43, 2, 25)?), 16);
+        assert_eq!(BookChapterVerse::new(1, 50, 26)?.verse_distance(&BookChapterVerse::new(2, 1, 1)?), 1);
+        assert_eq!(BookChapterVerse::first().verse_distance(&BookChapterVerse::last()), 31_101);
+
+        let mut verses = vec![BookChapterVerse::new(43, 3, 16)?, BookChapterVerse::new(1, 50, 26)?, BookChapterVerse::new(43, 2, 25)?];
+        verses.sort();
+        assert_eq!(verses, vec![BookChapterVerse::new(1, 50, 26)?, BookChapterVerse::new(43, 2, 25)?, BookChapterVerse::new(43, 3, 16)?]);
+
+        Ok(())
+    }
 }
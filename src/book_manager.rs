@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 
 use derive_more::{Deref, DerefMut, IntoIterator};
 use itertools::Itertools;
+use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
@@ -93,6 +94,9 @@ impl Default for BookManager {
     }
 }
 
+/// - The English [`BookManager`], so it only has to be built once
+pub static DEFAULT_BOOK_MANAGER: Lazy<BookManager> = Lazy::new(BookManager::default);
+
 // #[derive(Clone, Debug)]
 // pub struct BookInfo<'a> {
 //     id: u8,
@@ -135,6 +139,11 @@ impl<'a> BookManager {
         name.to_lowercase().trim_end_matches(".").trim().to_string()
     }
 
+    /// - Looks up a book by its exact name or one of its abbreviations (ignoring case and a trailing period)
+    pub fn book_id(&self, name: &str) -> Option<u8> {
+        self.abbreviations_to_book_id.get(&Self::normalize_book_name(name)).copied()
+    }
+
     pub fn parse_single_book_chapter_verse(&'a self, input: &'_ str) -> Option<BookSegment<ChapterVerse>> {
         let book_match = self.book_regex.find_iter(input).next()?;
         let book_name = &Self::normalize_book_name(book_match.as_str());
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
-use crate::{compare::SegmentCompare, passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange, full_chapter::FullChapter, full_chapter_range::FullChapterRange}, segment::PassageSegment};
+use crate::{book_chapter_verse::{verse_count, BookChapterVerse}, compare::{SegmentCompare, SegmentRelation}, passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange, full_chapter::FullChapter, full_chapter_range::FullChapterRange, range_pair::RangePair}, segment::PassageSegment};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// - Ordered by book, then by segment (see [`PassageSegment`]'s ordering)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct BookSegment<Segment: SegmentCompare> {
     pub book: u8,
     pub segment: Segment,
@@ -53,4 +54,219 @@ impl<Segment: SegmentCompare> BookSegment<Segment> {
             segment: self.segment.into(),
         }
     }
+
+    /// - Resolves this segment into the global verse ids of its first and last verse (see [`BookChapterVerse::as_verse`])
+    /// - Full chapters end at the last verse of the chapter (from [`crate::book_chapter_verse::BOOK_CHAPTER_VERSE_COUNT`])
+    /// - This method validates the book/chapter/verse numbers
+    pub fn verse_ids(&self) -> Result<RangePair<u16>, String> {
+        let start = BookChapterVerse::new(self.book, self.segment.starting_chapter(), self.segment.starting_verse())?;
+
+        let ending_chapter = self.segment.ending_chapter();
+        let ending_verse = match self.segment.ending_verse() {
+            Some(ending_verse) => ending_verse,
+            None => verse_count(self.book, ending_chapter)
+                .ok_or_else(|| format!("There is no 'Chapter {}' in 'Book {}'", ending_chapter, self.book))?,
+        };
+        let end = BookChapterVerse::new(self.book, ending_chapter, ending_verse)?;
+
+        let (start, end) = (start.as_verse(), end.as_verse());
+        if start > end {
+            Err(format!("'{}' ends before it starts", self.segment.actual()))?
+        }
+        Ok(RangePair::new(start, end))
+    }
+
+    /// - Every verse in this segment, in order
+    /// - Full chapters are expanded with [`crate::book_chapter_verse::BOOK_CHAPTER_VERSE_COUNT`]
+    /// - Chapters and verses that do not exist are skipped
+    pub fn verses(&self) -> impl Iterator<Item = BookChapterVerse> {
+        let BookSegment { book, segment } = *self;
+        segment.chapter_range().flat_map(move |chapter| {
+            let verse_count = verse_count(book, chapter).unwrap_or(0);
+            let start = if chapter == segment.starting_chapter() { segment.starting_verse().max(1) } else { 1 };
+            let end = match segment.ending_verse() {
+                Some(ending_verse) if chapter == segment.ending_chapter() => ending_verse.min(verse_count),
+                _ => verse_count,
+            };
+            (start..=end).map(move |verse| BookChapterVerse::from(BookSegment::chapter_verse(book, chapter, verse)))
+        })
+    }
+
+    /// - Unlike [`SegmentCompare::relation_to`], this knows how many verses are in each chapter and the order of the books
+    /// - So `Genesis 1:31` [`Meets`](SegmentRelation::Meets) `Genesis 2:1`, and `Genesis 50:26` [`Meets`](SegmentRelation::Meets) `Exodus 1:1`
+    /// - This method validates the book/chapter/verse numbers
+    pub fn relation_to(&self, other: &BookSegment<impl SegmentCompare>) -> Result<SegmentRelation, String> {
+        Ok(SegmentRelation::between(self.verse_ids()?, other.verse_ids()?, |end, start| end + 1 == start))
+    }
+
+    /// - Every verse of the other segment is in this one
+    pub fn contains(&self, other: &BookSegment<impl SegmentCompare>) -> Result<bool, String> {
+        Ok(self.relation_to(other)?.is_containing())
+    }
+
+    /// - Every verse of this segment is in the other one
+    pub fn is_contained_by(&self, other: &BookSegment<impl SegmentCompare>) -> Result<bool, String> {
+        Ok(self.relation_to(other)?.is_contained())
+    }
+
+    /// - Both segments cover exactly the same verses, so `John 3` covers the same as `John 3:1-36`
+    pub fn covers_same_as(&self, other: &BookSegment<impl SegmentCompare>) -> Result<bool, String> {
+        Ok(self.relation_to(other)? == SegmentRelation::Equals)
+    }
+
+    /// - There is no verse between the segments, but they do not share any either
+    pub fn is_adjacent_to(&self, other: &BookSegment<impl SegmentCompare>) -> Result<bool, String> {
+        Ok(self.relation_to(other)?.is_adjacent())
+    }
+
+    /// - The only verse the segments share is where one ends and the other starts
+    pub fn touches(&self, other: &BookSegment<impl SegmentCompare>) -> Result<bool, String> {
+        let (this, other) = (self.verse_ids()?, other.verse_ids()?);
+        Ok((this.end == other.start && this.start < other.start)
+            || (other.end == this.start && other.start < this.start))
+    }
+
+    /// - How many verses it takes to get from the end of the earlier segment to the start of the later one
+    /// - Segments that share any verse are `0` apart, and adjacent segments (`John 3:16`, `John 3:17`) are `1` apart
+    /// - This works across books, so `Genesis 50:26` and `Exodus 1:1` are `1` apart
+    /// - This method validates the book/chapter/verse numbers
+    pub fn verse_distance(&self, other: &BookSegment<impl SegmentCompare>) -> Result<u16, String> {
+        Ok(verse_id_distance(self.verse_ids()?, other.verse_ids()?))
+    }
+}
+
+/// - See [`BookSegment::verse_distance`]
+pub(crate) fn verse_id_distance(this: RangePair<u16>, other: RangePair<u16>) -> u16 {
+    // at most one side is positive, and both are 0 when the spans overlap
+    other.start.saturating_sub(this.end).max(this.start.saturating_sub(other.end))
+}
+
+#[cfg(test)]
+mod book_segment_tests {
+    use itertools::Itertools;
+
+    use crate::{compare::{SegmentCompare, SegmentRelation}, segment::PassageSegment};
+
+    use super::BookSegment;
+
+    #[test]
+    fn verse_ids() -> Result<(), String> {
+        assert_eq!(BookSegment::chapter_verse(1, 1, 1).verse_ids()?.start, 1);
+        assert_eq!(BookSegment::full_chapter(1, 1).verse_ids()?.end, 31);
+        assert_eq!(BookSegment::full_chapter_range(1, 1, 2).verse_ids()?.end, 56);
+        assert!(BookSegment::full_chapter(1, 51).verse_ids().is_err());
+        assert!(BookSegment::chapter_verse_range(1, 1, 3, 2).verse_ids().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn verses() -> Result<(), String> {
+        let ids = |seg: BookSegment<PassageSegment>| seg.verses().map(|verse| verse.as_id_string()).collect_vec();
+
+        // John 3:16-17
+        assert_eq!(ids(PassageSegment::chapter_verse_range(3, 16, 17).with_book(43)), vec!["43003016", "43003017"]);
+
+        // Genesis 1:31-2:1
+        assert_eq!(ids(PassageSegment::chapter_range(1, 31, 2, 1).with_book(1)), vec!["01001031", "01002001"]);
+
+        // Genesis 2-4
+        assert_eq!(BookSegment::full_chapter_range(1, 2, 4).verses().count(), 25 + 24 + 26);
+
+        // Genesis 5:12-6:6
+        assert_eq!(BookSegment::chapter_range(1, 5, 12, 6, 6).verses().count(), 21 + 6);
+
+        // Jude 1-2 (Jude only has 1 chapter of 25 verses)
+        assert_eq!(BookSegment::full_chapter_range(65, 1, 2).verses().count(), 25);
+
+        // Genesis 1:30-35 (Genesis 1 only has 31 verses)
+        assert_eq!(BookSegment::chapter_verse_range(1, 1, 30, 35).verses().count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn relation_to() -> Result<(), String> {
+        // Genesis 1:31 and Genesis 2:1
+        assert_eq!(
+            BookSegment::chapter_verse(1, 1, 31).relation_to(&BookSegment::chapter_verse(1, 2, 1))?,
+            SegmentRelation::Meets
+        );
+
+        // Genesis 50:26 and Exodus 1:1
+        assert_eq!(
+            BookSegment::chapter_verse(1, 50, 26).relation_to(&BookSegment::full_chapter(2, 1))?,
+            SegmentRelation::Meets
+        );
+
+        // John 3 and John 3:1-36
+        assert_eq!(
+            BookSegment::full_chapter(43, 3).relation_to(&BookSegment::chapter_verse_range(43, 3, 1, 36))?,
+            SegmentRelation::Equals
+        );
+
+        // John 3 and John 3:1-35
+        assert_eq!(
+            BookSegment::full_chapter(43, 3).relation_to(&BookSegment::chapter_verse_range(43, 3, 1, 35))?,
+            SegmentRelation::StartedBy
+        );
+
+        // John 3:36-4:1 and John 4
+        assert_eq!(
+            BookSegment::chapter_range(43, 3, 36, 4, 1).relation_to(&BookSegment::full_chapter(43, 4))?,
+            SegmentRelation::Overlaps
+        );
+
+        assert!(BookSegment::full_chapter(43, 30).relation_to(&BookSegment::full_chapter(43, 3)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn containment() -> Result<(), String> {
+        let john_3 = BookSegment::full_chapter(43, 3);
+        let john_3_16 = BookSegment::chapter_verse(43, 3, 16);
+
+        assert!(john_3.contains(&john_3_16)?);
+        assert!(!john_3_16.contains(&john_3)?);
+        assert!(john_3_16.is_contained_by(&john_3)?);
+        assert!(john_3.covers_same_as(&BookSegment::chapter_verse_range(43, 3, 1, 36))?);
+        assert!(john_3.is_adjacent_to(&BookSegment::chapter_verse(43, 4, 1))?);
+        assert!(john_3.touches(&BookSegment::chapter_range(43, 3, 36, 4, 2))?);
+        assert!(!john_3.touches(&john_3_16)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn verse_distance() -> Result<(), String> {
+        let john_3_16 = BookSegment::chapter_verse(43, 3, 16);
+        assert_eq!(john_3_16.verse_distance(&BookSegment::chapter_verse(43, 3, 16))?, 0);
+        assert_eq!(john_3_16.verse_distance(&BookSegment::chapter_verse(43, 3, 17))?, 1);
+        assert_eq!(john_3_16.verse_distance(&BookSegment::chapter_verse_range(43, 3, 1, 10))?, 6);
+        assert_eq!(john_3_16.verse_distance(&BookSegment::full_chapter(43, 4))?, 21);
+        assert_eq!(BookSegment::full_chapter(43, 3).verse_distance(&john_3_16)?, 0);
+
+        // Genesis 50 and Exodus 1:1
+        assert_eq!(BookSegment::full_chapter(1, 50).verse_distance(&BookSegment::chapter_verse(2, 1, 1))?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn order() {
+        let mut segments = vec![
+            BookSegment::chapter_verse(43, 3, 16).generalize(),
+            BookSegment::full_chapter(43, 3).generalize(),
+            BookSegment::chapter_verse_range(43, 3, 1, 20).generalize(),
+            BookSegment::full_chapter(1, 50).generalize(),
+            BookSegment::chapter_range(43, 2, 25, 3, 2).generalize(),
+        ];
+        segments.sort();
+        assert_eq!(segments, vec![
+            BookSegment::full_chapter(1, 50).generalize(),
+            BookSegment::chapter_range(43, 2, 25, 3, 2).generalize(),
+            BookSegment::chapter_verse_range(43, 3, 1, 20).generalize(),
+            BookSegment::full_chapter(43, 3).generalize(),
+            BookSegment::chapter_verse(43, 3, 16).generalize(),
+        ]);
+    }
 }
@@ -0,0 +1,359 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{book_chapter_verse::chapter_count, compare::SegmentCompare, passage_segments::full_chapter_range::FullChapterRange, segments::{BookPassageSegments, PassageSegments}};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Testament {
+    /// - Genesis to Malachi
+    Old,
+    /// - Matthew to Revelation
+    New,
+}
+
+/// - The kind of literature a book mostly is
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Genre {
+    Law,
+    History,
+    /// - Job to Song of Solomon, and Lamentations
+    Poetry,
+    Prophecy,
+    Gospel,
+    Epistle,
+    Apocalyptic,
+}
+
+/// - The sections the books are traditionally grouped into
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Grouping {
+    /// - Genesis to Deuteronomy
+    Pentateuch,
+    /// - Joshua to Esther, and Acts
+    /// - Unlike [`NamedRange::HistoricalBooks`], this has Acts, which is also history
+    History,
+    /// - Job to Song of Solomon
+    Wisdom,
+    /// - Isaiah to Daniel
+    MajorProphets,
+    /// - Hosea to Malachi
+    MinorProphets,
+    /// - Matthew to John
+    Gospels,
+    /// - Romans to Philemon
+    PaulineEpistles,
+    /// - Hebrews to Jude
+    GeneralEpistles,
+    /// - Revelation
+    Apocalypse,
+}
+
+/// - Who each book is traditionally attributed to, starting with Genesis
+/// - These follow tradition, not scholarly consensus
+const AUTHOR_TRADITIONS: [&str; 66] = [
+    "Moses", "Moses", "Moses", "Moses", "Moses",
+    "Joshua", "Samuel", "Samuel", "Samuel", "Samuel", "Jeremiah", "Jeremiah", "Ezra", "Ezra", "Ezra", "Nehemiah", "Mordecai",
+    "Unknown", "David", "Solomon", "Solomon", "Solomon",
+    "Isaiah", "Jeremiah", "Jeremiah", "Ezekiel", "Daniel",
+    "Hosea", "Joel", "Amos", "Obadiah", "Jonah", "Micah", "Nahum", "Habakkuk", "Zephaniah", "Haggai", "Zechariah", "Malachi",
+    "Matthew", "Mark", "Luke", "John",
+    "Luke",
+    "Paul", "Paul", "Paul", "Paul", "Paul", "Paul", "Paul", "Paul", "Paul", "Paul", "Paul", "Paul", "Paul",
+    "Unknown", "James", "Peter", "Peter", "John", "John", "John", "Jude",
+    "John",
+];
+
+/// - The SBL Handbook of Style abbreviations, starting with Genesis
+pub const SBL_ABBREVIATIONS: [&str; 66] = [
+    "Gen", "Exod", "Lev", "Num", "Deut", "Josh", "Judg", "Ruth", "1 Sam", "2 Sam", "1 Kgs", "2 Kgs", "1 Chr", "2 Chr", "Ezra", "Neh", "Esth",
+    "Job", "Ps", "Prov", "Eccl", "Song", "Isa", "Jer", "Lam", "Ezek", "Dan", "Hos", "Joel", "Amos", "Obad", "Jonah", "Mic", "Nah", "Hab", "Zeph", "Hag", "Zech", "Mal",
+    "Matt", "Mark", "Luke", "John", "Acts", "Rom", "1 Cor", "2 Cor", "Gal", "Eph", "Phil", "Col", "1 Thess", "2 Thess", "1 Tim", "2 Tim", "Titus", "Phlm",
+    "Heb", "Jas", "1 Pet", "2 Pet", "1 John", "2 John", "3 John", "Jude", "Rev",
+];
+
+/// - Metadata about a book of the Bible
+/// - See [`BookInfo::get`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BookInfo {
+    pub book: u8,
+    pub testament: Testament,
+    pub genre: Genre,
+    pub grouping: Grouping,
+    /// - Who the book is traditionally attributed to (`"Unknown"` when there is no strong tradition)
+    pub author_tradition: &'static str,
+    pub chapter_count: u8,
+}
+
+impl BookInfo {
+    /// - Returns `None` if the book is not between 1 (Genesis) and 66 (Revelation)
+    pub fn get(book: u8) -> Option<Self> {
+        let chapter_count = chapter_count(book)?;
+        let grouping = match book {
+            1..=5 => Grouping::Pentateuch,
+            6..=17 | 44 => Grouping::History,
+            18..=22 => Grouping::Wisdom,
+            23..=27 => Grouping::MajorProphets,
+            28..=39 => Grouping::MinorProphets,
+            40..=43 => Grouping::Gospels,
+            45..=57 => Grouping::PaulineEpistles,
+            58..=65 => Grouping::GeneralEpistles,
+            _ => Grouping::Apocalypse,
+        };
+        let genre = match grouping {
+            // Lamentations is poetry, even though it is grouped with the prophets
+            _ if book == 25 => Genre::Poetry,
+            Grouping::Pentateuch => Genre::Law,
+            Grouping::History => Genre::History,
+            Grouping::Wisdom => Genre::Poetry,
+            Grouping::MajorProphets | Grouping::MinorProphets => Genre::Prophecy,
+            Grouping::Gospels => Genre::Gospel,
+            Grouping::PaulineEpistles | Grouping::GeneralEpistles => Genre::Epistle,
+            Grouping::Apocalypse => Genre::Apocalyptic,
+        };
+        Some(Self {
+            book,
+            testament: if book <= 39 { Testament::Old } else { Testament::New },
+            genre,
+            grouping,
+            author_tradition: AUTHOR_TRADITIONS[(book - 1) as usize],
+            chapter_count,
+        })
+    }
+
+    /// - Every book from Genesis to Revelation
+    pub fn all() -> impl Iterator<Item = BookInfo> {
+        (1..=66).filter_map(BookInfo::get)
+    }
+}
+
+/// - Anything that can decide which books to keep, so it can be used to filter the organizers
+/// - Closures like `|book| book < 10` work as well
+pub trait BookFilter {
+    fn includes_book(&self, book: u8) -> bool;
+}
+
+impl<F: Fn(u8) -> bool> BookFilter for F {
+    fn includes_book(&self, book: u8) -> bool {
+        self(book)
+    }
+}
+
+impl BookFilter for Testament {
+    fn includes_book(&self, book: u8) -> bool {
+        BookInfo::get(book).is_some_and(|info| info.testament == *self)
+    }
+}
+
+impl BookFilter for Genre {
+    fn includes_book(&self, book: u8) -> bool {
+        BookInfo::get(book).is_some_and(|info| info.genre == *self)
+    }
+}
+
+impl BookFilter for Grouping {
+    fn includes_book(&self, book: u8) -> bool {
+        BookInfo::get(book).is_some_and(|info| info.grouping == *self)
+    }
+}
+
+/// - Well known ranges of books
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NamedRange {
+    OldTestament,
+    NewTestament,
+    /// - Genesis to Deuteronomy
+    Torah,
+    /// - Joshua to Esther
+    /// - Unlike [`Grouping::History`], this leaves out Acts, because a range is one run of books in the Old Testament
+    HistoricalBooks,
+    /// - Job to Song of Solomon
+    Wisdom,
+    /// - Isaiah to Malachi
+    Prophets,
+    /// - Isaiah to Daniel
+    MajorProphets,
+    /// - Hosea to Malachi
+    MinorProphets,
+    /// - Matthew to John
+    Gospels,
+    /// - Matthew, Mark, and Luke
+    Synoptics,
+    /// - Romans to Philemon
+    PaulineEpistles,
+    /// - Hebrews to Jude
+    GeneralEpistles,
+}
+
+impl NamedRange {
+    pub const ALL: [NamedRange; 12] = [
+        NamedRange::OldTestament,
+        NamedRange::NewTestament,
+        NamedRange::Torah,
+        NamedRange::HistoricalBooks,
+        NamedRange::Wisdom,
+        NamedRange::Prophets,
+        NamedRange::MajorProphets,
+        NamedRange::MinorProphets,
+        NamedRange::Gospels,
+        NamedRange::Synoptics,
+        NamedRange::PaulineEpistles,
+        NamedRange::GeneralEpistles,
+    ];
+
+    /// - The first and last book of this range
+    fn book_range(&self) -> std::ops::RangeInclusive<u8> {
+        match self {
+            NamedRange::OldTestament => 1..=39,
+            NamedRange::NewTestament => 40..=66,
+            NamedRange::Torah => 1..=5,
+            NamedRange::HistoricalBooks => 6..=17,
+            NamedRange::Wisdom => 18..=22,
+            NamedRange::Prophets => 23..=39,
+            NamedRange::MajorProphets => 23..=27,
+            NamedRange::MinorProphets => 28..=39,
+            NamedRange::Gospels => 40..=43,
+            NamedRange::Synoptics => 40..=42,
+            NamedRange::PaulineEpistles => 45..=57,
+            NamedRange::GeneralEpistles => 58..=65,
+        }
+    }
+
+    /// - Every book in this range, in order
+    pub fn books(&self) -> Vec<u8> {
+        self.book_range().collect()
+    }
+
+    /// - One passage per book, covering every chapter of it
+    pub fn passages(&self) -> Vec<BookPassageSegments> {
+        self.book_range()
+            .map(|book| {
+                let chapters = chapter_count(book).expect("Every named range only has books of the Bible");
+                PassageSegments(vec![FullChapterRange::new(1, chapters).actual()]).with_book(book)
+            })
+            .collect()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NamedRange::OldTestament => "Old Testament",
+            NamedRange::NewTestament => "New Testament",
+            NamedRange::Torah => "Torah",
+            NamedRange::HistoricalBooks => "Historical Books",
+            NamedRange::Wisdom => "Wisdom",
+            NamedRange::Prophets => "Prophets",
+            NamedRange::MajorProphets => "Major Prophets",
+            NamedRange::MinorProphets => "Minor Prophets",
+            NamedRange::Gospels => "Gospels",
+            NamedRange::Synoptics => "Synoptics",
+            NamedRange::PaulineEpistles => "Pauline Epistles",
+            NamedRange::GeneralEpistles => "General Epistles",
+        }
+    }
+}
+
+impl BookFilter for NamedRange {
+    fn includes_book(&self, book: u8) -> bool {
+        self.book_range().contains(&book)
+    }
+}
+
+impl Display for NamedRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// - Matches the [`name`](NamedRange::name) ignoring case and spaces, as well as a few common aliases
+/// - Ex: `"Pentateuch"`, `"nt"`, `"Catholic Epistles"`
+impl FromStr for NamedRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+        let alias = match key.as_str() {
+            "ot" => Some(NamedRange::OldTestament),
+            "nt" => Some(NamedRange::NewTestament),
+            "pentateuch" | "law" => Some(NamedRange::Torah),
+            "history" => Some(NamedRange::HistoricalBooks),
+            "wisdomliterature" | "poetry" => Some(NamedRange::Wisdom),
+            "synopticgospels" => Some(NamedRange::Synoptics),
+            "paulineletters" | "paul" => Some(NamedRange::PaulineEpistles),
+            "catholicepistles" => Some(NamedRange::GeneralEpistles),
+            _ => None,
+        };
+        alias
+            .or_else(|| NamedRange::ALL.into_iter().find(|range| range.name().replace(' ', "").to_lowercase() == key))
+            .ok_or_else(|| format!("'{}' is not a known range of books", s))
+    }
+}
+
+#[cfg(test)]
+mod canon_tests {
+    use crate::segment::PassageSegment;
+
+    use super::{BookFilter, BookInfo, Genre, Grouping, NamedRange, Testament};
+
+    #[test]
+    fn book_info() {
+        let genesis = BookInfo::get(1).unwrap();
+        assert_eq!(genesis.testament, Testament::Old);
+        assert_eq!(genesis.genre, Genre::Law);
+        assert_eq!(genesis.grouping, Grouping::Pentateuch);
+        assert_eq!(genesis.author_tradition, "Moses");
+        assert_eq!(genesis.chapter_count, 50);
+
+        let lamentations = BookInfo::get(25).unwrap();
+        assert_eq!(lamentations.genre, Genre::Poetry);
+        assert_eq!(lamentations.grouping, Grouping::MajorProphets);
+
+        let acts = BookInfo::get(44).unwrap();
+        assert_eq!((acts.testament, acts.genre, acts.author_tradition), (Testament::New, Genre::History, "Luke"));
+
+        let revelation = BookInfo::get(66).unwrap();
+        assert_eq!((revelation.genre, revelation.chapter_count), (Genre::Apocalyptic, 22));
+
+        assert!(BookInfo::get(0).is_none());
+        assert!(BookInfo::get(67).is_none());
+        assert_eq!(BookInfo::all().count(), 66);
+        assert_eq!(BookInfo::all().filter(|info| info.testament == Testament::New).count(), 27);
+        assert_eq!(BookInfo::all().filter(|info| info.author_tradition == "Paul").count(), 13);
+    }
+
+    #[test]
+    fn named_ranges() {
+        assert_eq!(NamedRange::Torah.books(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(NamedRange::Synoptics.books(), vec![40, 41, 42]);
+        assert_eq!(NamedRange::GeneralEpistles.books().len(), 8);
+        assert_eq!(NamedRange::OldTestament.books().len() + NamedRange::NewTestament.books().len(), 66);
+        let history: Vec<u8> = (1..=66).filter(|&book| BookInfo::get(book).unwrap().grouping == Grouping::History).collect();
+        assert_eq!(history, [NamedRange::HistoricalBooks.books(), vec![44]].concat());
+
+        // Jude has only one chapter
+        let passages = NamedRange::GeneralEpistles.passages();
+        assert_eq!((passages[0].book, passages[0].segments.0.clone()), (58, vec![PassageSegment::full_chapter_range(1, 13)]));
+        assert_eq!((passages[7].book, passages[7].segments.0.clone()), (65, vec![PassageSegment::full_chapter(1)]));
+    }
+
+    #[test]
+    fn parse_named_ranges() {
+        assert_eq!("Major Prophets".parse(), Ok(NamedRange::MajorProphets));
+        assert_eq!("majorprophets".parse(), Ok(NamedRange::MajorProphets));
+        assert_eq!("Pentateuch".parse(), Ok(NamedRange::Torah));
+        assert_eq!("NT".parse(), Ok(NamedRange::NewTestament));
+        assert!("Apocrypha".parse::<NamedRange>().is_err());
+
+        for range in NamedRange::ALL {
+            assert_eq!(range.to_string().parse(), Ok(range));
+        }
+    }
+
+    #[test]
+    fn filters() {
+        assert!(Testament::New.includes_book(40));
+        assert!(!Testament::New.includes_book(39));
+        assert!(Genre::Poetry.includes_book(25));
+        assert!(Grouping::PaulineEpistles.includes_book(57));
+        assert!(!Grouping::PaulineEpistles.includes_book(58));
+        assert!(NamedRange::Wisdom.includes_book(19));
+        assert!((|book: u8| book == 43).includes_book(43));
+    }
+}
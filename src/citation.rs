@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use crate::{book_manager::BookManager, book_segment::BookSegment, label::BookNameStyle, segment::PassageSegment, segments::{BookPassageSegments, PassageSegments}};
+
+/// - Renders references in a house style
+/// - Implement this for a custom style, or use one of the [`CitationStyle`] presets
+pub trait ReferenceFormatter {
+    /// - Returns an error if the book is not known
+    fn format_passage(&self, manager: &BookManager, passage: &BookPassageSegments) -> Result<String, String>;
+
+    /// - Returns an error if the book is not known
+    fn format_segment(&self, manager: &BookManager, segment: &BookSegment<PassageSegment>) -> Result<String, String> {
+        self.format_passage(manager, &PassageSegments(vec![segment.segment]).with_book(segment.book))
+    }
+}
+
+/// - What goes between the start and end of a range
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RangeDash {
+    /// - `8:28-30`
+    Hyphen,
+    /// - `8:28–30`
+    EnDash,
+}
+
+impl RangeDash {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RangeDash::Hyphen => "-",
+            RangeDash::EnDash => "–",
+        }
+    }
+}
+
+/// - A [`ReferenceFormatter`] that covers most house styles
+/// - The segments are written like [`BookPassageSegments::label`] (`Romans 8:28, 30; 9:1`)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CitationStyle {
+    pub book_names: BookNameStyle,
+    pub dash: RangeDash,
+    /// - Passages of only full chapters are written with `ch.` or `chs.` (`Romans chs. 1–3`)
+    pub label_chapters: bool,
+}
+
+impl CitationStyle {
+    /// - `Rom 8:28–30`
+    pub const SBL: CitationStyle = CitationStyle { book_names: BookNameStyle::Sbl, dash: RangeDash::EnDash, label_chapters: false };
+    /// - `Romans 8:28–30` and `Romans chs. 1–3`
+    pub const CHICAGO: CitationStyle = CitationStyle { book_names: BookNameStyle::Name, dash: RangeDash::EnDash, label_chapters: true };
+    /// - `Romans 8:28–30`
+    pub const APA: CitationStyle = CitationStyle { book_names: BookNameStyle::Name, dash: RangeDash::EnDash, label_chapters: false };
+    /// - `Romans 8:28-30`
+    pub const PLAIN: CitationStyle = CitationStyle { book_names: BookNameStyle::Name, dash: RangeDash::Hyphen, label_chapters: false };
+    /// - `Rom 8:28-30` (with the abbreviations of the [`BookManager`])
+    pub const ABBREVIATED: CitationStyle = CitationStyle { book_names: BookNameStyle::Abbreviation, dash: RangeDash::Hyphen, label_chapters: false };
+}
+
+impl Default for CitationStyle {
+    fn default() -> Self {
+        Self::PLAIN
+    }
+}
+
+impl FromStr for CitationStyle {
+    type Err = String;
+
+    /// - The name of a preset, ignoring case: `sbl`, `chicago`, `apa`, `plain`, or `abbreviated`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sbl" => Ok(Self::SBL),
+            "chicago" => Ok(Self::CHICAGO),
+            "apa" => Ok(Self::APA),
+            "plain" => Ok(Self::PLAIN),
+            "abbreviated" | "abbreviation" | "abbr" => Ok(Self::ABBREVIATED),
+            _ => Err(format!("'{}' is not a known citation style (sbl, chicago, apa, plain, abbreviated)", s)),
+        }
+    }
+}
+
+impl ReferenceFormatter for CitationStyle {
+    fn format_passage(&self, manager: &BookManager, passage: &BookPassageSegments) -> Result<String, String> {
+        let book = self.book_names.book_name(manager, passage.book)?;
+        // labels only hold digits and `:,; -`, so the only dashes are between the ends of ranges
+        let label = passage.label().replace('-', self.dash.as_str());
+
+        let is_only_full_chapters = !passage.segments.is_empty()
+            && passage.segments.iter().all(|seg| matches!(seg, PassageSegment::FullChapter(_) | PassageSegment::FullChapterRange(_)));
+        if self.label_chapters && is_only_full_chapters {
+            let is_one_chapter = matches!(passage.segments.as_slice(), [PassageSegment::FullChapter(_)]);
+            let chapters = if is_one_chapter { "ch." } else { "chs." };
+            return Ok(format!("{} {} {}", book, chapters, label));
+        }
+        Ok(format!("{} {}", book, label))
+    }
+}
+
+#[cfg(test)]
+mod citation_tests {
+    use crate::{book_manager::{BookManager, DEFAULT_BOOK_MANAGER}, book_segment::BookSegment, segments::BookPassageSegments};
+
+    use super::{CitationStyle, ReferenceFormatter};
+
+    fn format(style: CitationStyle, book: u8, input: &str) -> String {
+        style.format_passage(&DEFAULT_BOOK_MANAGER, &BookPassageSegments::parse(book, input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(format(CitationStyle::SBL, 45, "8:28-30"), "Rom 8:28–30");
+        assert_eq!(format(CitationStyle::CHICAGO, 45, "8:28-30"), "Romans 8:28–30");
+        assert_eq!(format(CitationStyle::APA, 45, "8:28-30"), "Romans 8:28–30");
+        assert_eq!(format(CitationStyle::PLAIN, 45, "8:28-30"), "Romans 8:28-30");
+        assert_eq!(format(CitationStyle::ABBREVIATED, 43, "8:28-30"), "Jn 8:28-30");
+
+        assert_eq!(format(CitationStyle::SBL, 46, "13"), "1 Cor 13");
+        assert_eq!(format(CitationStyle::SBL, 43, "3:16, 3:18-4:2"), "John 3:16, 18–4:2");
+    }
+
+    #[test]
+    fn chapters() {
+        assert_eq!(format(CitationStyle::CHICAGO, 45, "1-3"), "Romans chs. 1–3");
+        assert_eq!(format(CitationStyle::CHICAGO, 45, "8"), "Romans ch. 8");
+        assert_eq!(format(CitationStyle::CHICAGO, 45, "1, 3-4"), "Romans chs. 1, 3–4");
+        // only when every segment is a full chapter
+        assert_eq!(format(CitationStyle::CHICAGO, 45, "1, 2:1"), "Romans 1; 2:1");
+        assert_eq!(format(CitationStyle::SBL, 45, "1-3"), "Rom 1–3");
+    }
+
+    #[test]
+    fn parse_presets() {
+        assert_eq!("SBL".parse::<CitationStyle>().unwrap(), CitationStyle::SBL);
+        assert_eq!(" chicago ".parse::<CitationStyle>().unwrap(), CitationStyle::CHICAGO);
+        assert_eq!("abbr".parse::<CitationStyle>().unwrap(), CitationStyle::ABBREVIATED);
+        assert!("mla".parse::<CitationStyle>().is_err());
+    }
+
+    #[test]
+    fn segments() {
+        let segment = BookSegment::chapter_verse_range(45, 8, 28, 30).generalize();
+        assert_eq!(CitationStyle::SBL.format_segment(&DEFAULT_BOOK_MANAGER, &segment).unwrap(), "Rom 8:28–30");
+
+        let unknown = BookSegment::full_chapter(67, 1).generalize();
+        assert!(CitationStyle::SBL.format_segment(&DEFAULT_BOOK_MANAGER, &unknown).is_err());
+        assert!(CitationStyle::PLAIN.format_segment(&DEFAULT_BOOK_MANAGER, &unknown).is_err());
+    }
+
+    #[test]
+    fn custom_formatter() {
+        struct Shouting;
+
+        impl ReferenceFormatter for Shouting {
+            fn format_passage(&self, manager: &BookManager, passage: &BookPassageSegments) -> Result<String, String> {
+                Ok(CitationStyle::PLAIN.format_passage(manager, passage)?.to_uppercase())
+            }
+        }
+
+        let segment = BookSegment::chapter_verse(43, 3, 16).generalize();
+        assert_eq!(Shouting.format_segment(&DEFAULT_BOOK_MANAGER, &segment).unwrap(), "JOHN 3:16");
+    }
+}
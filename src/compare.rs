@@ -1,7 +1,100 @@
 use std::ops::Bound;
 use std::fmt::Debug;
 
-use crate::{passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange, full_chapter::FullChapter, full_chapter_range::FullChapterRange}, segment::PassageSegment, book_segment::BookSegment};
+use crate::{passage_segments::{chapter_range::ChapterRange, chapter_verse::ChapterVerse, chapter_verse_range::ChapterVerseRange, full_chapter::FullChapter, full_chapter_range::FullChapterRange, range_pair::RangePair}, segment::PassageSegment, book_segment::BookSegment};
+
+/// - How one segment relates to another, following Allen's interval algebra
+/// - Read it as `this` is `relation` `other`, so `1:1-3` [`Meets`](SegmentRelation::Meets) `1:4-5`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SegmentRelation {
+    /// - Ex: `1:1-2` and `1:4-5`
+    Before,
+    /// - This ends on the verse right before the other one starts
+    /// - Ex: `1:1-3` and `1:4-5`
+    Meets,
+    /// - Ex: `1:1-4` and `1:3-5`
+    Overlaps,
+    /// - Ex: `1:1-2` and `1:1-5`
+    Starts,
+    /// - Ex: `1:2-3` and `1:1-5`
+    During,
+    /// - Ex: `1:4-5` and `1:1-5`
+    Finishes,
+    /// - Ex: `1:1-5` and `1:1-5`
+    Equals,
+    /// - Ex: `1:1-5` and `1:4-5`
+    FinishedBy,
+    /// - Ex: `1:1-5` and `1:2-3`
+    Contains,
+    /// - Ex: `1:1-5` and `1:1-2`
+    StartedBy,
+    /// - Ex: `1:3-5` and `1:1-4`
+    OverlappedBy,
+    /// - This starts on the verse right after the other one ends
+    /// - Ex: `1:4-5` and `1:1-3`
+    MetBy,
+    /// - Ex: `1:4-5` and `1:1-2`
+    After,
+}
+
+impl SegmentRelation {
+    /// - The relation of the other segment to this one
+    pub fn inverse(self) -> Self {
+        match self {
+            SegmentRelation::Before => SegmentRelation::After,
+            SegmentRelation::Meets => SegmentRelation::MetBy,
+            SegmentRelation::Overlaps => SegmentRelation::OverlappedBy,
+            SegmentRelation::Starts => SegmentRelation::StartedBy,
+            SegmentRelation::During => SegmentRelation::Contains,
+            SegmentRelation::Finishes => SegmentRelation::FinishedBy,
+            SegmentRelation::Equals => SegmentRelation::Equals,
+            SegmentRelation::FinishedBy => SegmentRelation::Finishes,
+            SegmentRelation::Contains => SegmentRelation::During,
+            SegmentRelation::StartedBy => SegmentRelation::Starts,
+            SegmentRelation::OverlappedBy => SegmentRelation::Overlaps,
+            SegmentRelation::MetBy => SegmentRelation::Meets,
+            SegmentRelation::After => SegmentRelation::Before,
+        }
+    }
+
+    /// - Every verse of the other segment is in this one
+    pub fn is_containing(self) -> bool {
+        matches!(self, SegmentRelation::Equals | SegmentRelation::Contains | SegmentRelation::StartedBy | SegmentRelation::FinishedBy)
+    }
+
+    /// - Every verse of this segment is in the other one
+    pub fn is_contained(self) -> bool {
+        self.inverse().is_containing()
+    }
+
+    /// - There is no verse between the segments, but they do not share any either
+    pub fn is_adjacent(self) -> bool {
+        matches!(self, SegmentRelation::Meets | SegmentRelation::MetBy)
+    }
+
+    /// - Both ranges are inclusive
+    /// - `follows(end, start)` is whether `start` is the point right after `end`
+    pub(crate) fn between<T: Ord + Copy>(this: RangePair<T>, other: RangePair<T>, follows: impl Fn(T, T) -> bool) -> Self {
+        use std::cmp::Ordering::*;
+        if this.end < other.start {
+            return if follows(this.end, other.start) { SegmentRelation::Meets } else { SegmentRelation::Before };
+        }
+        if other.end < this.start {
+            return if follows(other.end, this.start) { SegmentRelation::MetBy } else { SegmentRelation::After };
+        }
+        match (this.start.cmp(&other.start), this.end.cmp(&other.end)) {
+            (Equal, Equal) => SegmentRelation::Equals,
+            (Equal, Less) => SegmentRelation::Starts,
+            (Equal, Greater) => SegmentRelation::StartedBy,
+            (Greater, Equal) => SegmentRelation::Finishes,
+            (Less, Equal) => SegmentRelation::FinishedBy,
+            (Greater, Less) => SegmentRelation::During,
+            (Less, Greater) => SegmentRelation::Contains,
+            (Less, Less) => SegmentRelation::Overlaps,
+            (Greater, Greater) => SegmentRelation::OverlappedBy,
+        }
+    }
+}
 
 pub trait SegmentCompare: Copy + Sized + Debug +  Into<PassageSegment> {
     fn starting_verse(&self) -> u8;
@@ -59,6 +152,57 @@ pub trait SegmentCompare: Copy + Sized + Debug +  Into<PassageSegment> {
         !(self.ends_before(other) || self.starts_after(other))
     }
 
+    /// - The `(chapter, verse)` of the first and last verse
+    /// - A segment that ends with a full chapter ends at verse [`u8::MAX`], meaning the rest of the chapter
+    fn chapter_verse_bounds(&self) -> RangePair<(u8, u8)> {
+        RangePair {
+            start: (self.starting_chapter(), self.starting_verse()),
+            end: (self.ending_chapter(), self.ending_verse().unwrap_or(u8::MAX)),
+        }
+    }
+
+    /// - Segments don't know how many verses are in a chapter, so `1:31` is [`SegmentRelation::Before`] `2:1`
+    /// - For an exact relation, use [`BookSegment::relation_to`]
+    fn relation_to(&self, other: &impl SegmentCompare) -> SegmentRelation {
+        SegmentRelation::between(self.chapter_verse_bounds(), other.chapter_verse_bounds(), |(chapter, verse), next| {
+            if verse == u8::MAX {
+                next == (chapter + 1, 1)
+            } else {
+                next == (chapter, verse + 1)
+            }
+        })
+    }
+
+    /// - Every verse of the other segment is in this one
+    fn contains(&self, other: &impl SegmentCompare) -> bool {
+        self.relation_to(other).is_containing()
+    }
+
+    /// - Every verse of this segment is in the other one
+    fn is_contained_by(&self, other: &impl SegmentCompare) -> bool {
+        other.contains(self)
+    }
+
+    /// - Both segments cover exactly the same verses, even if they are of different kinds
+    /// - Ex: `1` and `1-1`, or `1:2-2` and `1:2`
+    fn covers_same_as(&self, other: &impl SegmentCompare) -> bool {
+        self.relation_to(other) == SegmentRelation::Equals
+    }
+
+    /// - There is no verse between the segments, but they do not share any either
+    /// - Ex: `1:1-3` and `1:4`
+    fn is_adjacent_to(&self, other: &impl SegmentCompare) -> bool {
+        self.relation_to(other).is_adjacent()
+    }
+
+    /// - The only verse the segments share is where one ends and the other starts
+    /// - Ex: `1:1-3` and `1:3-5`
+    fn touches(&self, other: &impl SegmentCompare) -> bool {
+        let (this, other) = (self.chapter_verse_bounds(), other.chapter_verse_bounds());
+        (this.end == other.start && this.start < other.start)
+            || (other.end == this.start && other.start < this.start)
+    }
+
     /// determines what kind of passage segment this really is
     fn actual(&self) -> PassageSegment {
         let starting_chapter = self.starting_chapter();
@@ -524,4 +668,71 @@ mod tests {
         assert!(!double_overlap(this, PassageSegment::full_chapter_range(5, 6)));
       
     }
+
+    // --------- //
+    // Relations //
+    // --------- //
+
+    fn double_relation(this: impl SegmentCompare, other: impl SegmentCompare) -> SegmentRelation {
+        let relation = this.relation_to(&other);
+        assert_eq!(relation.inverse(), other.relation_to(&this));
+        relation
+    }
+
+    #[test]
+    fn relation_to() {
+        let this = PassageSegment::chapter_verse_range(3, 3, 7);
+
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse_range(3, 9, 10)), SegmentRelation::Before);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse_range(3, 8, 10)), SegmentRelation::Meets);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse_range(3, 5, 10)), SegmentRelation::Overlaps);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse_range(3, 3, 10)), SegmentRelation::Starts);
+        assert_eq!(double_relation(this, PassageSegment::full_chapter(3)), SegmentRelation::During);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse_range(3, 1, 7)), SegmentRelation::Finishes);
+        assert_eq!(double_relation(this, PassageSegment::chapter_range(3, 3, 3, 7)), SegmentRelation::Equals);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse(3, 7)), SegmentRelation::FinishedBy);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse(3, 5)), SegmentRelation::Contains);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse(3, 3)), SegmentRelation::StartedBy);
+        assert_eq!(double_relation(this, PassageSegment::chapter_range(2, 5, 3, 4)), SegmentRelation::OverlappedBy);
+        assert_eq!(double_relation(this, PassageSegment::chapter_range(2, 5, 3, 2)), SegmentRelation::MetBy);
+        assert_eq!(double_relation(this, PassageSegment::full_chapter(2)), SegmentRelation::After);
+    }
+
+    #[test]
+    fn relation_to_full_chapters() {
+        let this = PassageSegment::full_chapter(3);
+
+        assert_eq!(double_relation(this, PassageSegment::full_chapter(4)), SegmentRelation::Meets);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse(4, 1)), SegmentRelation::Meets);
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse(4, 2)), SegmentRelation::Before);
+        assert_eq!(double_relation(this, PassageSegment::full_chapter_range(3, 3)), SegmentRelation::Equals);
+        assert_eq!(double_relation(this, PassageSegment::full_chapter_range(2, 3)), SegmentRelation::Finishes);
+        assert_eq!(double_relation(this, PassageSegment::chapter_range(3, 1, 4, 1)), SegmentRelation::Starts);
+        // the last verse of the chapter is unknown
+        assert_eq!(double_relation(this, PassageSegment::chapter_verse_range(3, 1, 36)), SegmentRelation::StartedBy);
+        assert_eq!(double_relation(PassageSegment::chapter_verse(2, 25), this), SegmentRelation::Before);
+    }
+
+    #[test]
+    fn containment() {
+        let chapter = PassageSegment::full_chapter(3);
+        let verses = PassageSegment::chapter_verse_range(3, 1, 3);
+
+        assert!(chapter.contains(&verses));
+        assert!(!verses.contains(&chapter));
+        assert!(verses.is_contained_by(&chapter));
+        assert!(chapter.contains(&chapter));
+
+        assert!(chapter.covers_same_as(&PassageSegment::full_chapter_range(3, 3)));
+        assert!(PassageSegment::chapter_verse_range(3, 2, 2).covers_same_as(&PassageSegment::chapter_verse(3, 2)));
+        assert!(!chapter.covers_same_as(&verses));
+
+        assert!(verses.is_adjacent_to(&PassageSegment::chapter_verse(3, 4)));
+        assert!(!verses.is_adjacent_to(&PassageSegment::chapter_verse(3, 3)));
+
+        assert!(verses.touches(&PassageSegment::chapter_verse_range(3, 3, 5)));
+        assert!(PassageSegment::chapter_verse_range(3, 3, 5).touches(&verses));
+        assert!(!verses.touches(&PassageSegment::chapter_verse_range(3, 2, 5)));
+        assert!(!verses.touches(&PassageSegment::chapter_verse(3, 4)));
+    }
 }
@@ -0,0 +1,123 @@
+use crate::{book_chapter_verse::{BookChapterVerse, LAST_VERSE}, book_segment::BookSegment, compare::SegmentCompare, normalize::segments_from_verse_ids, passage_segments::{full_chapter_range::FullChapterRange, range_pair::RangePair}, segment::PassageSegment};
+
+/// - How far a context window may grow around a segment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContextBounds {
+    /// - Stays within the chapters the segment starts and ends in
+    #[default]
+    Chapter,
+    /// - Stays within the book of the segment
+    Book,
+    /// - Only stops at `Genesis 1:1` and `Revelation 22:21`, so it may reach into other books
+    Bible,
+}
+
+impl<Segment: SegmentCompare> BookSegment<Segment> {
+    /// - Grows this segment by `before` verses at the start and `after` verses at the end
+    /// - The window is clamped to the `bounds`, and may cross chapters within them
+    /// - There is one segment for each book the window reaches (only [`ContextBounds::Bible`] can reach more than one)
+    /// - This method validates the book/chapter/verse numbers
+    pub fn with_context(&self, before: u16, after: u16, bounds: ContextBounds) -> Result<Vec<BookSegment<PassageSegment>>, String> {
+        let ids = self.verse_ids()?;
+        let (lower, upper) = match bounds {
+            ContextBounds::Chapter => (
+                BookChapterVerse::new(self.book, self.segment.starting_chapter(), 1)?.as_verse(),
+                BookChapterVerse::last_of_chapter(self.book, self.segment.ending_chapter())?.as_verse(),
+            ),
+            ContextBounds::Book => (
+                BookChapterVerse::new(self.book, 1, 1)?.as_verse(),
+                BookChapterVerse::last_of_book(self.book)?.as_verse(),
+            ),
+            ContextBounds::Bible => (1, LAST_VERSE),
+        };
+        let start = ids.start.saturating_sub(before).max(lower);
+        let end = ids.end.saturating_add(after).min(upper);
+        segments_from_verse_ids(RangePair::new(start, end))
+    }
+
+    /// - Grows this segment out to every chapter it has any verse in
+    pub fn with_full_chapters(&self) -> BookSegment<PassageSegment> {
+        FullChapterRange::new(self.segment.starting_chapter(), self.segment.ending_chapter())
+            .actual()
+            .with_book(self.book)
+    }
+}
+
+#[cfg(test)]
+mod context_tests {
+    use crate::{book_segment::BookSegment, compare::SegmentCompare, segment::PassageSegment};
+
+    use super::ContextBounds;
+
+    #[test]
+    fn with_context() -> Result<(), String> {
+        // John 3:16 with 2 verses on each side
+        assert_eq!(
+            BookSegment::chapter_verse(43, 3, 16).with_context(2, 2, ContextBounds::Chapter)?,
+            vec![PassageSegment::chapter_verse_range(3, 14, 18).with_book(43)]
+        );
+
+        // John 3:35 stays in chapter 3
+        assert_eq!(
+            BookSegment::chapter_verse(43, 3, 35).with_context(1, 5, ContextBounds::Chapter)?,
+            vec![PassageSegment::chapter_verse_range(3, 34, 36).with_book(43)]
+        );
+
+        // ... unless the whole book is allowed
+        assert_eq!(
+            BookSegment::chapter_verse(43, 3, 35).with_context(1, 5, ContextBounds::Book)?,
+            vec![PassageSegment::chapter_range(3, 34, 4, 4).with_book(43)]
+        );
+
+        // covering the whole chapter
+        assert_eq!(
+            BookSegment::chapter_verse_range(43, 3, 2, 35).with_context(1, 1, ContextBounds::Chapter)?,
+            vec![PassageSegment::full_chapter(3).with_book(43)]
+        );
+
+        // invalid segments
+        assert!(BookSegment::chapter_verse(43, 3, 37).with_context(1, 1, ContextBounds::Chapter).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn across_books() -> Result<(), String> {
+        // Genesis 50:26 stays in Genesis
+        assert_eq!(
+            BookSegment::chapter_verse(1, 50, 26).with_context(0, 2, ContextBounds::Book)?,
+            vec![PassageSegment::chapter_verse(50, 26).with_book(1)]
+        );
+
+        // ... unless the whole Bible is allowed
+        assert_eq!(
+            BookSegment::chapter_verse(1, 50, 26).with_context(0, 2, ContextBounds::Bible)?,
+            vec![
+                PassageSegment::chapter_verse(50, 26).with_book(1),
+                PassageSegment::chapter_verse_range(1, 1, 2).with_book(2),
+            ]
+        );
+
+        // saturates at the ends of the Bible
+        assert_eq!(
+            BookSegment::chapter_verse(1, 1, 2).with_context(5, 0, ContextBounds::Bible)?,
+            vec![PassageSegment::chapter_verse_range(1, 1, 2).with_book(1)]
+        );
+        assert_eq!(
+            BookSegment::chapter_verse(66, 22, 20).with_context(0, 5, ContextBounds::Bible)?,
+            vec![PassageSegment::chapter_verse_range(22, 20, 21).with_book(66)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn with_full_chapters() {
+        assert_eq!(
+            BookSegment::chapter_verse(43, 3, 16).with_full_chapters(),
+            PassageSegment::full_chapter(3).with_book(43)
+        );
+        assert_eq!(
+            BookSegment::chapter_range(43, 3, 16, 5, 2).with_full_chapters(),
+            PassageSegment::full_chapter_range(3, 5).with_book(43)
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{book_manager::BookManager, parse::segment_input_len, segments::{BookPassageSegments, PassageSegments}};
+
+/// - Abbreviations that are also English words, so `is 6` in lowercase is not Isaiah 6 (but `is 6:1` is)
+pub const WORD_ABBREVIATIONS: [&str; 21] = [
+    "am", "is", "so", "ex", "act", "acts", "pro", "mar", "mark", "re", "la", "ho", "co", "de", "es", "na", "mi", "ti", "ob", "ac", "job",
+];
+
+/// - Segments that start with a chapter and verse (`3:16` or `3.16`)
+static CHAPTER_VERSE_START: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ *\d+ *[:.] *\d").unwrap());
+
+/// - What can come between the numbers of segments (see [`segment_input_len`])
+const SEGMENT_SEPARATORS: [char; 9] = ['.', ',', ':', ';', '-', '–', '—', '—', '⸺'];
+
+/// - A reference found in free text by [`BookManager::extract_references`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReferenceMatch<'a> {
+    pub passage: BookPassageSegments,
+    /// - The byte range of the reference in the input, from the start of the book name to the last digit
+    pub span: Range<usize>,
+    /// - The reference as it was written (`Jn.3.16`)
+    pub text: &'a str,
+}
+
+impl BookManager {
+    /// - Finds every book name or abbreviation followed by segments, like `John 3:16` or `Jn.3.16-18, 20`
+    /// - The segments are only parsed, not validated, so `John 30:1` is still found
+    /// - Numbers after a reference that cannot be part of it are left out (`John 3:16, 300 people` finds `John 3:16`)
+    /// - Abbreviations that are also words (see [`WORD_ABBREVIATIONS`]) are only found when capitalized (`Am 5`) or followed by a chapter and verse (`am 5:1`), so `I am 5 years old` has no references
+    pub fn extract_references<'a>(&self, input: &'a str) -> Vec<ReferenceMatch<'a>> {
+        self.book_regex.find_iter(input).filter_map(|book_match| {
+            let book = self.book_id(book_match.as_str())?;
+            let start = book_match.end();
+            let mut end = start + segment_input_len(&input[start..])?;
+            // drop trailing numbers until the rest parses
+            let segments = loop {
+                match PassageSegments::parse(&input[start..end]) {
+                    Ok(segments) => break segments,
+                    Err(_) => end = start + input[start..end].rfind(SEGMENT_SEPARATORS)?,
+                }
+                end = start + input[start..end].trim_end().len();
+            };
+            let written = book_match.as_str().trim_end_matches('.');
+            let is_word = WORD_ABBREVIATIONS.contains(&written.to_lowercase().as_str());
+            if is_word && !written.starts_with(char::is_uppercase) && !CHAPTER_VERSE_START.is_match(&input[start..end]) {
+                return None;
+            }
+            Some(ReferenceMatch {
+                passage: segments.with_book(book),
+                span: book_match.start()..end,
+                text: &input[book_match.start()..end],
+            })
+        }).collect()
+    }
+
+    /// - Replaces every reference found by [`BookManager::extract_references`] with what `replace` returns for it
+    /// - Everything outside of the references is kept byte-for-byte
+    /// - Return [`ReferenceMatch::text`] to keep a reference as it was
+    pub fn rewrite(&self, input: &str, mut replace: impl FnMut(&ReferenceMatch) -> String) -> String {
+        let mut output = String::with_capacity(input.len());
+        let mut last_end = 0;
+        for reference in self.extract_references(input) {
+            output.push_str(&input[last_end..reference.span.start]);
+            output.push_str(&replace(&reference));
+            last_end = reference.span.end;
+        }
+        output.push_str(&input[last_end..]);
+        output
+    }
+
+    /// - Like [`BookManager::rewrite`], but `replace` can fail, which stops the rewrite and returns the error
+    pub fn try_rewrite(&self, input: &str, mut replace: impl FnMut(&ReferenceMatch) -> Result<String, String>) -> Result<String, String> {
+        let mut error = None;
+        let output = self.rewrite(input, |reference| {
+            if error.is_some() {
+                return String::new();
+            }
+            replace(reference).unwrap_or_else(|e| {
+                error = Some(e);
+                String::new()
+            })
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod extract_tests {
+    use crate::{book_manager::DEFAULT_BOOK_MANAGER, citation::{CitationStyle, ReferenceFormatter}, segments::BookPassageSegments};
+
+    #[test]
+    fn extract() {
+        let input = "Read Jn.3.16 and 1 Cor 13:4-7, then Genesis 1; 2:1-3. Johnson 3 is not a book.";
+        let references = DEFAULT_BOOK_MANAGER.extract_references(input);
+        let found: Vec<(&str, BookPassageSegments)> = references.iter().map(|r| (r.text, r.passage.clone())).collect();
+        assert_eq!(found, vec![
+            ("Jn.3.16", BookPassageSegments::parse(43, "3:16").unwrap()),
+            ("1 Cor 13:4-7", BookPassageSegments::parse(46, "13:4-7").unwrap()),
+            ("Genesis 1; 2:1-3", BookPassageSegments::parse(1, "1; 2:1-3").unwrap()),
+        ]);
+        for reference in references {
+            assert_eq!(&input[reference.span], reference.text);
+        }
+
+        // only parsed, not validated
+        assert_eq!(DEFAULT_BOOK_MANAGER.extract_references("John 30:1").len(), 1);
+        assert!(DEFAULT_BOOK_MANAGER.extract_references("John wrote this").is_empty());
+    }
+
+    #[test]
+    fn prose_is_not_a_reference() {
+        // `am` is Amos, but 300 is not a chapter
+        assert!(DEFAULT_BOOK_MANAGER.extract_references("I am 300 years old").is_empty());
+        // the part that parses is kept
+        let found: Vec<&str> = DEFAULT_BOOK_MANAGER.extract_references("See John 3.16.17 and Gen 1-2-3").iter().map(|r| r.text).collect();
+        assert_eq!(found, vec!["John 3.16", "Gen 1-2"]);
+        assert!(DEFAULT_BOOK_MANAGER.extract_references("Acts 2024").is_empty());
+        assert_eq!(DEFAULT_BOOK_MANAGER.rewrite("I am 300 years old", |_| String::from("!")), "I am 300 years old");
+
+        let found: Vec<&str> = DEFAULT_BOOK_MANAGER.extract_references("In 2024, Acts 2024 and Acts 2:4").iter().map(|r| r.text).collect();
+        assert_eq!(found, vec!["Acts 2:4"]);
+    }
+
+    #[test]
+    fn words_are_not_books() {
+        for input in ["I am 5 years old", "this is 6 inches", "I am 5 years old and this is 6 inches.", "so 3 of them, job 2 done"] {
+            assert!(DEFAULT_BOOK_MANAGER.extract_references(input).is_empty(), "{}", input);
+            assert_eq!(DEFAULT_BOOK_MANAGER.rewrite(input, |_| String::from("!")), input);
+        }
+
+        // capitalized, or with a chapter and verse, they are still references
+        let found: Vec<&str> = DEFAULT_BOOK_MANAGER.extract_references("Am 5, is 6:1, and Job 2").iter().map(|r| r.text).collect();
+        assert_eq!(found, vec!["Am 5", "is 6:1", "Job 2"]);
+    }
+
+    #[test]
+    fn trailing_numbers() {
+        let found = |input: &str| -> Vec<(String, BookPassageSegments)> {
+            DEFAULT_BOOK_MANAGER.extract_references(input).into_iter().map(|r| (r.text.to_string(), r.passage)).collect()
+        };
+        assert_eq!(found("Read John 3:16. 5 people came."), vec![(String::from("John 3:16"), BookPassageSegments::parse(43, "3:16").unwrap())]);
+        assert_eq!(found("See John 3:16, 300 people"), vec![(String::from("John 3:16"), BookPassageSegments::parse(43, "3:16").unwrap())]);
+        assert_eq!(found("See John 3:16-18, 20, 300"), vec![(String::from("John 3:16-18, 20"), BookPassageSegments::parse(43, "3:16-18, 20").unwrap())]);
+
+        let normalized = DEFAULT_BOOK_MANAGER.rewrite("See Jn 3:16, 300 people", |reference| reference.passage.label());
+        assert_eq!(normalized, "See 3:16, 300 people");
+    }
+
+    #[test]
+    fn rewrite() {
+        let input = "See Jn.3.16,\tand  Rom 8:28–30.\n";
+        let normalized = DEFAULT_BOOK_MANAGER.rewrite(input, |reference| {
+            CitationStyle::PLAIN.format_passage(&DEFAULT_BOOK_MANAGER, &reference.passage).unwrap()
+        });
+        assert_eq!(normalized, "See John 3:16,\tand  Romans 8:28-30.\n");
+
+        let linked = DEFAULT_BOOK_MANAGER.rewrite(input, |reference| format!("[{}](#{})", reference.text, reference.passage.book));
+        assert_eq!(linked, "See [Jn.3.16](#43),\tand  [Rom 8:28–30](#45).\n");
+
+        // keeping every reference keeps the input
+        assert_eq!(DEFAULT_BOOK_MANAGER.rewrite(input, |reference| reference.text.to_string()), input);
+    }
+
+    #[test]
+    fn try_rewrite() {
+        let input = "John 3:16 and Revelation 30:1";
+        let result = DEFAULT_BOOK_MANAGER.try_rewrite(input, |reference| {
+            reference.passage.verse_count()?;
+            Ok(reference.passage.label())
+        });
+        assert!(result.is_err());
+        assert_eq!(DEFAULT_BOOK_MANAGER.try_rewrite("John 3:16!", |reference| Ok(reference.passage.label())).unwrap(), "3:16!");
+    }
+}
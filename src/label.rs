@@ -0,0 +1,151 @@
+use crate::{book_chapter_verse::verse_count, book_manager::BookManager, canon::SBL_ABBREVIATIONS, compare::SegmentCompare, segment::PassageSegment, segments::BookPassageSegments};
+
+/// - Which name of the book to start a label with (see [`BookPassageSegments::label_with_book`])
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BookNameStyle {
+    /// - Ex: `John 3:16`
+    #[default]
+    Name,
+    /// - Ex: `Jn 3:16`
+    Abbreviation,
+    /// - The SBL Handbook of Style abbreviations, which do not depend on the [`BookManager`]
+    /// - Ex: `Rom 8:28`
+    Sbl,
+}
+
+impl BookNameStyle {
+    /// - Returns an error if the book has no name in this style
+    pub fn book_name(&self, manager: &BookManager, book: u8) -> Result<String, String> {
+        let name = match self {
+            BookNameStyle::Name => manager.book_id_to_name.get(&book).map(String::as_str),
+            BookNameStyle::Abbreviation => manager.book_id_to_abbreviation.get(&book).map(String::as_str),
+            BookNameStyle::Sbl => (book as usize).checked_sub(1).and_then(|idx| SBL_ABBREVIATIONS.get(idx)).copied(),
+        };
+        name.map(String::from).ok_or_else(|| format!("There is no 'Book {}' in the Bible", book))
+    }
+}
+
+impl PassageSegment {
+    fn is_full_chapters(&self) -> bool {
+        matches!(self, PassageSegment::FullChapter(_) | PassageSegment::FullChapterRange(_))
+    }
+
+    /// - The segment without its starting chapter, for when the label is already in that chapter
+    /// - Ex: `16-18` for `3:16-18`, or `36-4:2` for `3:36-4:2`
+    fn label_in_chapter(&self) -> String {
+        match self {
+            PassageSegment::ChapterVerse(seg) => seg.verse.to_string(),
+            PassageSegment::ChapterVerseRange(seg) => format!("{}-{}", seg.verses.start, seg.verses.end),
+            PassageSegment::ChapterRange(seg) => format!("{}-{}:{}", seg.start.verse, seg.end.chapter, seg.end.verse),
+            PassageSegment::FullChapter(_) | PassageSegment::FullChapterRange(_) => self.to_string(),
+        }
+    }
+}
+
+impl BookPassageSegments {
+    /// - A compact label that leaves out repeated chapters, like `1:1-4, 5-7; 2:2-3:4, 6`
+    /// - `,` joins segments that continue in the chapter the previous one ended in (and lists of full chapters, like `1, 3-4`)
+    /// - `;` comes before every segment that names a new chapter
+    /// - The parser reads a bare number after verses as a verse, so full chapters after verses are written with their verses (`3:16; 5:1-47`)
+    /// - The segments are kept in order, so [`normalize`](BookPassageSegments::normalize) first for the shortest label
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        // the chapter the previous segment ended in, when it ended with a verse
+        let mut verse_chapter: Option<u8> = None;
+        let mut previous_was_full_chapters = false;
+        let mut has_verses = false;
+        for (idx, seg) in self.segments.iter().enumerate() {
+            let seg = if has_verses { self.with_verses(seg) } else { *seg };
+            let (separator, text) = if seg.is_full_chapters() {
+                (if previous_was_full_chapters { ", " } else { "; " }, seg.to_string())
+            } else if verse_chapter == Some(seg.starting_chapter()) {
+                (", ", seg.label_in_chapter())
+            } else {
+                ("; ", seg.to_string())
+            };
+            if idx > 0 {
+                label.push_str(separator);
+            }
+            label.push_str(&text);
+
+            previous_was_full_chapters = seg.is_full_chapters();
+            has_verses |= !previous_was_full_chapters;
+            verse_chapter = if previous_was_full_chapters { None } else { Some(seg.ending_chapter()) };
+        }
+        label
+    }
+
+    /// - Full chapters as the verses they hold (`5` -> `5:1-47`), or as they are when the book or chapter is unknown
+    fn with_verses(&self, seg: &PassageSegment) -> PassageSegment {
+        if !seg.is_full_chapters() {
+            return *seg;
+        }
+        let (start, end) = (seg.starting_chapter(), seg.ending_chapter());
+        match verse_count(self.book, end) {
+            Some(last_verse) if start == end => PassageSegment::chapter_verse_range(start, 1, last_verse),
+            Some(last_verse) => PassageSegment::chapter_range(start, 1, end, last_verse),
+            None => *seg,
+        }
+    }
+
+    /// - The label with the book name from the [`BookManager`], like `John 3:16, 18; 4:1`
+    /// - Returns an error if the book has no name in the style
+    pub fn label_with_book(&self, manager: &BookManager, style: BookNameStyle) -> Result<String, String> {
+        Ok(format!("{} {}", style.book_name(manager, self.book)?, self.label()))
+    }
+}
+
+#[cfg(test)]
+mod label_tests {
+    use crate::{book_manager::DEFAULT_BOOK_MANAGER, segment::PassageSegment, segments::{BookPassageSegments, PassageSegments}};
+
+    use super::BookNameStyle;
+
+    fn label(input: &str) -> String {
+        BookPassageSegments::parse(43, input).unwrap().label()
+    }
+
+    #[test]
+    fn repeated_chapters() {
+        assert_eq!(label("3:16, 3:18, 4:1"), "3:16, 18; 4:1");
+        assert_eq!(label("1:1-4, 1:5-7, 2:2-3:4, 3:6"), "1:1-4, 5-7; 2:2-3:4, 6");
+        assert_eq!(label("3:16-18, 3:20-4:2, 4:5"), "3:16-18, 20-4:2, 5");
+        assert_eq!(label("3:16, 5:1"), "3:16; 5:1");
+    }
+
+    #[test]
+    fn full_chapters() {
+        assert_eq!(label("1, 2-4, 5:1-3, 5:7"), "1, 2-4; 5:1-3, 7");
+        assert_eq!(label("5, 3:16"), "5; 3:16");
+
+        // a bare chapter after verses would be read back as a verse
+        let passage = PassageSegments(vec![PassageSegment::chapter_verse(3, 16), PassageSegment::full_chapter(5), PassageSegment::full_chapter_range(7, 8)]);
+        assert_eq!(passage.with_book(43).label(), "3:16; 5:1-47; 7:1-8:59");
+        assert_eq!(PassageSegments::new().with_book(43).label(), "");
+    }
+
+    #[test]
+    fn parses_back() {
+        for input in ["3:16, 18; 4:1", "1:1-4, 5-7; 2:2-3:4, 6", "1, 2-4; 5:1-3, 7", "3:16-18, 20-4:2, 5"] {
+            assert_eq!(label(input), input);
+        }
+
+        // `John 5, 3:16` normalizes to 3:16 then all of chapter 5, which must not read back as 3:16 and 3:5
+        let passage = BookPassageSegments::parse(43, "5, 3:16").unwrap().normalize().unwrap();
+        let label = passage.label();
+        assert_eq!(label, "3:16; 5:1-47");
+        assert_eq!(BookPassageSegments::parse(43, &label).unwrap().normalize().unwrap(), passage);
+    }
+
+    #[test]
+    fn with_book() {
+        let passage = BookPassageSegments::parse(43, "3:16, 3:18, 4:1").unwrap();
+        assert_eq!(passage.label_with_book(&DEFAULT_BOOK_MANAGER, BookNameStyle::Name).unwrap(), "John 3:16, 18; 4:1");
+        assert_eq!(passage.label_with_book(&DEFAULT_BOOK_MANAGER, BookNameStyle::Abbreviation).unwrap(), "Jn 3:16, 18; 4:1");
+        let passage = BookPassageSegments::parse(46, "13:4-7").unwrap();
+        assert_eq!(passage.label_with_book(&DEFAULT_BOOK_MANAGER, BookNameStyle::Sbl).unwrap(), "1 Cor 13:4-7");
+
+        let passage = BookPassageSegments::parse(67, "1").unwrap();
+        assert!(passage.label_with_book(&DEFAULT_BOOK_MANAGER, BookNameStyle::Name).is_err());
+    }
+}
@@ -2,9 +2,18 @@ pub mod bible;
 pub mod book_manager;
 pub mod bible_organizer;
 pub mod bible_verse_organizer;
+pub mod book;
+pub mod canon;
+pub mod citation;
 pub mod book_chapter_verse;
 pub mod compare;
+pub mod label;
+pub mod context;
+pub mod extract;
+pub mod link;
 pub mod maps;
+pub mod metrics;
+pub mod normalize;
 pub mod organizer;
 pub mod parse;
 pub mod passage;
@@ -12,3 +21,7 @@ pub mod passage_segments;
 pub mod segment;
 pub mod book_segment;
 pub mod segments;
+pub mod set_operations;
+pub mod similarity;
+pub mod spoken;
+pub mod verse_set;
@@ -0,0 +1,289 @@
+use std::str::FromStr;
+
+use crate::{book_chapter_verse::verse_count, book_manager::BookManager, book_segment::BookSegment, canon::SBL_ABBREVIATIONS, compare::SegmentCompare, passage::{Template, TemplatePlaceholder}, segment::PassageSegment, segments::BookPassageSegments};
+
+/// - A value that can be put into a [`LinkBuilder`] template with `{name}`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LinkPlaceholder {
+    /// - `{book}`: the book name from the [`BookManager`]
+    Book,
+    /// - `{book_slug}`: the book name for URLs, like `1-corinthians`
+    BookSlug,
+    /// - `{osis}`: the OSIS reference, like `John.3.16-John.3.18`
+    Osis,
+    /// - `{chapter}`
+    Chapter,
+    /// - `{verse}`
+    Verse,
+    /// - `{end_chapter}`
+    EndChapter,
+    /// - `{end_verse}`
+    EndVerse,
+    /// - `{label}`: the reference being linked, like `3:16-18`
+    Label,
+}
+
+impl TemplatePlaceholder for LinkPlaceholder {
+    fn name(&self) -> &'static str {
+        match self {
+            LinkPlaceholder::Book => "book",
+            LinkPlaceholder::BookSlug => "book_slug",
+            LinkPlaceholder::Osis => "osis",
+            LinkPlaceholder::Chapter => "chapter",
+            LinkPlaceholder::Verse => "verse",
+            LinkPlaceholder::EndChapter => "end_chapter",
+            LinkPlaceholder::EndVerse => "end_verse",
+            LinkPlaceholder::Label => "label",
+        }
+    }
+}
+
+impl FromStr for LinkPlaceholder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "book" => LinkPlaceholder::Book,
+            "book_slug" => LinkPlaceholder::BookSlug,
+            "osis" => LinkPlaceholder::Osis,
+            "chapter" => LinkPlaceholder::Chapter,
+            "verse" => LinkPlaceholder::Verse,
+            "end_chapter" => LinkPlaceholder::EndChapter,
+            "end_verse" => LinkPlaceholder::EndVerse,
+            "label" => LinkPlaceholder::Label,
+            _ => Err(format!("Unknown link placeholder '{{{}}}'", s))?,
+        })
+    }
+}
+
+impl BookSegment<PassageSegment> {
+    /// - Ex: `John.3.16`, `John.3.16-John.3.18`, `John.3`, or `John.1-John.3`
+    /// - The OSIS books are the SBL abbreviations without spaces (`1Cor`)
+    /// - Returns an error if the book is not one of the 66
+    pub fn osis(&self) -> Result<String, String> {
+        let book = (self.book as usize).checked_sub(1).and_then(|idx| SBL_ABBREVIATIONS.get(idx))
+            .map(|abbreviation| abbreviation.replace(' ', ""))
+            .ok_or_else(|| format!("There is no 'Book {}' in the Bible", self.book))?;
+        Ok(match self.segment {
+            PassageSegment::ChapterVerse(seg) => format!("{book}.{}.{}", seg.chapter, seg.verse),
+            PassageSegment::ChapterVerseRange(seg) => format!("{book}.{}.{}-{book}.{}.{}", seg.chapter, seg.verses.start, seg.chapter, seg.verses.end),
+            PassageSegment::ChapterRange(seg) => format!("{book}.{}.{}-{book}.{}.{}", seg.start.chapter, seg.start.verse, seg.end.chapter, seg.end.verse),
+            PassageSegment::FullChapter(seg) => format!("{book}.{}", seg.chapter),
+            PassageSegment::FullChapterRange(seg) => format!("{book}.{}-{book}.{}", seg.start.chapter, seg.end.chapter),
+        })
+    }
+}
+
+/// - The book name from the [`BookManager`] for URLs, lowercase and joined with `-`
+/// - Ex: `1-corinthians`, `song-of-solomon`
+pub fn book_slug(manager: &BookManager, book: u8) -> Result<String, String> {
+    let name = manager.book_id_to_name.get(&book).ok_or_else(|| format!("There is no 'Book {}' in the Bible", book))?;
+    Ok(name.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>().join("-"))
+}
+
+/// - Encodes everything but letters, digits, and `-._~:` so placeholder values can not break the URL
+fn percent_encode(value: &str) -> String {
+    value.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b':' => (byte as char).to_string(),
+        _ => format!("%{:02X}", byte),
+    }).collect()
+}
+
+/// - Encodes what can not be in a URL (spaces, quotes, `<>`, and anything not ASCII) in the text of a template, and keeps `/?#&=%` and the like
+fn encode_url_text(url: &str) -> String {
+    url.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b':' | b'/' | b'?' | b'#' | b'[' | b']' | b'@'
+            | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' | b'%' => (byte as char).to_string(),
+        _ => format!("%{:02X}", byte),
+    }).collect()
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&#39;")
+}
+
+fn escape_markdown_text(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('[', "\\[").replace(']', "\\]")
+}
+
+fn escape_markdown_title(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// - Turns references into links with a URL template, like `/bible/{osis}` or `/{book_slug}/{chapter}#v{verse}`
+/// - The templates can use `{book}`, `{book_slug}`, `{osis}`, `{chapter}`, `{verse}`, `{end_chapter}`, `{end_verse}`, and `{label}` (like `3:16-18`)
+/// - Full chapters start at verse 1 and end at the last verse of the chapter
+/// - Everything is plain string generation, so nothing is looked up online
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkBuilder {
+    /// - Placeholder values are percent-encoded, and so is text that can not be in a URL (like spaces)
+    pub url: Template<LinkPlaceholder>,
+    /// - The text of the link (`{book} {label}` by default)
+    pub text: Template<LinkPlaceholder>,
+    /// - The tooltip of the link (none by default)
+    pub title: Option<Template<LinkPlaceholder>>,
+}
+
+impl LinkBuilder {
+    /// - Returns an error if the template does not parse or uses a placeholder that is not for links (like `{content}`)
+    pub fn new(url: &str) -> Result<Self, String> {
+        Ok(Self {
+            url: url.parse()?,
+            text: Template::parse("{book} {label}")?,
+            title: None,
+        })
+    }
+
+    /// - Returns an error if the template does not parse or uses a placeholder that is not for links
+    pub fn with_text(mut self, text: &str) -> Result<Self, String> {
+        self.text = text.parse()?;
+        Ok(self)
+    }
+
+    /// - Returns an error if the template does not parse or uses a placeholder that is not for links
+    pub fn with_title(mut self, title: &str) -> Result<Self, String> {
+        self.title = Some(title.parse()?);
+        Ok(self)
+    }
+
+    /// - The chapters and verses start with `first` and end with `last`
+    fn values(manager: &BookManager, book: u8, first: PassageSegment, last: PassageSegment, osis: String, label: String) -> Result<impl Fn(LinkPlaceholder) -> Option<String>, String> {
+        let name = manager.book_id_to_name.get(&book).cloned()
+            .ok_or_else(|| format!("There is no 'Book {}' in the Bible", book))?;
+        let slug = book_slug(manager, book)?;
+        let end_verse = last.ending_verse().or_else(|| verse_count(book, last.ending_chapter()))
+            .ok_or_else(|| format!("There is no chapter {} in 'Book {}'", last.ending_chapter(), book))?;
+        Ok(move |placeholder| Some(match placeholder {
+            LinkPlaceholder::Book => name.clone(),
+            LinkPlaceholder::BookSlug => slug.clone(),
+            LinkPlaceholder::Osis => osis.clone(),
+            LinkPlaceholder::Chapter => first.starting_chapter().to_string(),
+            LinkPlaceholder::Verse => first.starting_verse().to_string(),
+            LinkPlaceholder::EndChapter => last.ending_chapter().to_string(),
+            LinkPlaceholder::EndVerse => end_verse.to_string(),
+            LinkPlaceholder::Label => label.clone(),
+        }))
+    }
+
+    /// - Values are percent-encoded before the rest of the URL, so `%2C` in a value stays as it is
+    fn fill_url(&self, values: impl Fn(LinkPlaceholder) -> Option<String>) -> String {
+        encode_url_text(&self.url.fill(|placeholder| values(placeholder).map(|value| percent_encode(&value))))
+    }
+
+    fn segment_values(manager: &BookManager, segment: &BookSegment<PassageSegment>) -> Result<impl Fn(LinkPlaceholder) -> Option<String>, String> {
+        Self::values(manager, segment.book, segment.segment, segment.segment, segment.osis()?, segment.segment.to_string())
+    }
+
+    /// - Returns an error if the [`BookManager`] does not know the book, or the chapter does not exist
+    pub fn url(&self, manager: &BookManager, segment: &BookSegment<PassageSegment>) -> Result<String, String> {
+        Ok(self.fill_url(Self::segment_values(manager, segment)?))
+    }
+
+    /// - One URL for every segment of a passage, like `/bible/John.3.16,John.3.18`
+    /// - `{osis}` joins the segments with `,`, `{label}` is [`BookPassageSegments::label`], and the chapters and verses go from the start of the first segment to the end of the last
+    /// - Returns an error if the passage is empty, the [`BookManager`] does not know the book, or the last chapter does not exist
+    pub fn passage_url(&self, manager: &BookManager, passage: &BookPassageSegments) -> Result<String, String> {
+        let (Some(first), Some(last)) = (passage.segments.first(), passage.segments.last()) else {
+            return Err(String::from("There are no segments to link to"));
+        };
+        let osis: Vec<String> = passage.iter().map(|seg| seg.osis()).collect::<Result<_, _>>()?;
+        Ok(self.fill_url(Self::values(manager, passage.book, *first, *last, osis.join(","), passage.label())?))
+    }
+
+    /// - Ex: `[John 3:16](/bible/John.3.16 "title")`
+    /// - Returns an error if the [`BookManager`] does not know the book, or the chapter does not exist
+    pub fn markdown(&self, manager: &BookManager, segment: &BookSegment<PassageSegment>) -> Result<String, String> {
+        let values = Self::segment_values(manager, segment)?;
+        let url = self.url(manager, segment)?;
+        let text = escape_markdown_text(&self.text.fill(&values));
+        Ok(match &self.title {
+            Some(title) => format!("[{}]({} \"{}\")", text, url, escape_markdown_title(&title.fill(&values))),
+            None => format!("[{}]({})", text, url),
+        })
+    }
+
+    /// - Ex: `<a href="/bible/John.3.16" title="title">John 3:16</a>`
+    /// - Returns an error if the [`BookManager`] does not know the book, or the chapter does not exist
+    pub fn html(&self, manager: &BookManager, segment: &BookSegment<PassageSegment>) -> Result<String, String> {
+        let values = Self::segment_values(manager, segment)?;
+        let url = escape_html(&self.url(manager, segment)?);
+        let text = escape_html(&self.text.fill(&values));
+        Ok(match &self.title {
+            Some(title) => format!("<a href=\"{}\" title=\"{}\">{}</a>", url, escape_html(&title.fill(&values)), text),
+            None => format!("<a href=\"{}\">{}</a>", url, text),
+        })
+    }
+}
+
+#[cfg(test)]
+mod link_tests {
+    use crate::{book_manager::DEFAULT_BOOK_MANAGER, book_segment::BookSegment, segments::{BookPassageSegments, PassageSegments}};
+
+    use super::{book_slug, LinkBuilder};
+
+    #[test]
+    fn osis() {
+        assert_eq!(BookSegment::chapter_verse(43, 3, 16).generalize().osis().unwrap(), "John.3.16");
+        assert_eq!(BookSegment::chapter_verse_range(43, 3, 16, 18).generalize().osis().unwrap(), "John.3.16-John.3.18");
+        assert_eq!(BookSegment::chapter_range(46, 12, 31, 13, 13).generalize().osis().unwrap(), "1Cor.12.31-1Cor.13.13");
+        assert_eq!(BookSegment::full_chapter(19, 23).generalize().osis().unwrap(), "Ps.23");
+        assert_eq!(BookSegment::full_chapter_range(1, 1, 3).generalize().osis().unwrap(), "Gen.1-Gen.3");
+        assert!(BookSegment::full_chapter(67, 1).generalize().osis().is_err());
+    }
+
+    #[test]
+    fn slugs() {
+        assert_eq!(book_slug(&DEFAULT_BOOK_MANAGER, 46).unwrap(), "1-corinthians");
+        assert_eq!(book_slug(&DEFAULT_BOOK_MANAGER, 22).unwrap(), "song-of-solomon");
+    }
+
+    #[test]
+    fn urls() {
+        let segment = BookSegment::chapter_verse_range(43, 3, 16, 18).generalize();
+        let url = |template: &str| LinkBuilder::new(template).unwrap().url(&DEFAULT_BOOK_MANAGER, &segment).unwrap();
+        assert_eq!(url("/bible/{osis}"), "/bible/John.3.16-John.3.18");
+        assert_eq!(url("/{book_slug}/{chapter}#v{verse}"), "/john/3#v16");
+        assert_eq!(url("/{book_slug}/{chapter}/{verse}-{end_verse}"), "/john/3/16-18");
+        // the text of the template is encoded too, so the URL works as a Markdown link destination
+        assert_eq!(url("/search?q={book} {label}"), "/search?q=John%203:16-18");
+        assert_eq!(url("/é/{osis}?a=1&b=\"{chapter}\""), "/%C3%A9/John.3.16-John.3.18?a=1&b=%223%22");
+        assert_eq!(url("/search?q={book}%20{label}"), "/search?q=John%203:16-18");
+
+        // full chapters end at their last verse
+        let chapter = BookSegment::full_chapter(43, 3).generalize();
+        let builder = LinkBuilder::new("/{book_slug}/{chapter}/{verse}-{end_chapter}:{end_verse}").unwrap();
+        assert_eq!(builder.url(&DEFAULT_BOOK_MANAGER, &chapter).unwrap(), "/john/3/1-3:36");
+        assert!(builder.url(&DEFAULT_BOOK_MANAGER, &BookSegment::full_chapter(43, 30).generalize()).is_err());
+
+        // only link placeholders
+        assert!(LinkBuilder::new("/{content}").is_err());
+        assert!(LinkBuilder::new("/bible").unwrap().with_text("{content}").is_err());
+    }
+
+    #[test]
+    fn passage_urls() {
+        let builder = LinkBuilder::new("/bible/{osis}?label={label}&end={end_chapter}:{end_verse}").unwrap();
+        let passage = BookPassageSegments::parse(43, "3:16, 3:18-4:2").unwrap();
+        assert_eq!(builder.passage_url(&DEFAULT_BOOK_MANAGER, &passage).unwrap(), "/bible/John.3.16%2CJohn.3.18-John.4.2?label=3:16%2C%2018-4:2&end=4:2");
+        assert!(builder.passage_url(&DEFAULT_BOOK_MANAGER, &PassageSegments::new().with_book(43)).is_err());
+        assert!(builder.passage_url(&DEFAULT_BOOK_MANAGER, &BookPassageSegments::parse(67, "1").unwrap()).is_err());
+    }
+
+    #[test]
+    fn anchors() {
+        let segment = BookSegment::chapter_verse(43, 3, 16).generalize();
+        let builder = LinkBuilder::new("/bible/{osis}").unwrap();
+        assert_eq!(builder.markdown(&DEFAULT_BOOK_MANAGER, &segment).unwrap(), "[John 3:16](/bible/John.3.16)");
+        assert_eq!(builder.html(&DEFAULT_BOOK_MANAGER, &segment).unwrap(), "<a href=\"/bible/John.3.16\">John 3:16</a>");
+
+        let builder = builder.with_text("[{book}] {label}").unwrap().with_title("\"{book}\" & <{osis}>").unwrap();
+        assert_eq!(
+            builder.markdown(&DEFAULT_BOOK_MANAGER, &segment).unwrap(),
+            r#"[\[John\] 3:16](/bible/John.3.16 "\"John\" & <John.3.16>")"#
+        );
+        assert_eq!(
+            builder.html(&DEFAULT_BOOK_MANAGER, &segment).unwrap(),
+            "<a href=\"/bible/John.3.16\" title=\"&quot;John&quot; &amp; &lt;John.3.16&gt;\">[John] 3:16</a>"
+        );
+    }
+}
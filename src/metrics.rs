@@ -0,0 +1,116 @@
+use std::{collections::BTreeSet, fmt::Debug};
+
+use crate::{bible_verse_organizer::BibleVerseOrganizer, book_segment::BookSegment, compare::SegmentCompare, segments::BookPassageSegments};
+
+impl<Segment: SegmentCompare> BookSegment<Segment> {
+    /// - Full chapters are counted with [`crate::book_chapter_verse::BOOK_CHAPTER_VERSE_COUNT`]
+    /// - This method validates the book/chapter/verse numbers
+    pub fn verse_count(&self) -> Result<u16, String> {
+        let ids = self.verse_ids()?;
+        Ok(ids.end - ids.start + 1)
+    }
+
+    /// - Counts every chapter this segment has any verse in
+    /// - This method validates the book/chapter/verse numbers
+    pub fn chapter_count(&self) -> Result<u8, String> {
+        self.verse_ids()?;
+        Ok(self.segment.ending_chapter() - self.segment.starting_chapter() + 1)
+    }
+
+    /// - Sums `measure` over the content of every verse in this segment
+    /// - Verses without content are skipped
+    pub fn measure_content<Content: Debug + Default>(&self, bible: &BibleVerseOrganizer<Content>, measure: impl Fn(&Content) -> usize) -> usize {
+        bible.iter_segment_content(self).map(|psg| measure(psg.content)).sum()
+    }
+
+    pub fn word_count(&self, bible: &BibleVerseOrganizer<String>) -> usize {
+        self.measure_content(bible, |content| content.split_whitespace().count())
+    }
+
+    pub fn character_count(&self, bible: &BibleVerseOrganizer<String>) -> usize {
+        self.measure_content(bible, |content| content.chars().count())
+    }
+}
+
+/// - Verses shared by multiple segments are only counted once
+/// - These methods validate the book/chapter/verse numbers
+impl BookPassageSegments {
+    pub fn verse_count(&self) -> Result<u16, String> {
+        Ok(self.merged_verse_ids()?.iter().map(|ids| ids.end - ids.start + 1).sum())
+    }
+
+    /// - Counts every chapter any segment has any verse in
+    pub fn chapter_count(&self) -> Result<u8, String> {
+        let chapters: BTreeSet<u8> = self.normalize()?.iter()
+            .flat_map(|seg| seg.segment.chapter_range())
+            .collect();
+        Ok(chapters.len() as u8)
+    }
+
+    /// - Sums `measure` over the content of every verse in this passage
+    /// - Verses without content are skipped
+    pub fn measure_content<Content: Debug + Default>(&self, bible: &BibleVerseOrganizer<Content>, measure: impl Fn(&Content) -> usize) -> Result<usize, String> {
+        Ok(self.normalize()?.iter().map(|seg| seg.measure_content(bible, &measure)).sum())
+    }
+
+    pub fn word_count(&self, bible: &BibleVerseOrganizer<String>) -> Result<usize, String> {
+        self.measure_content(bible, |content| content.split_whitespace().count())
+    }
+
+    pub fn character_count(&self, bible: &BibleVerseOrganizer<String>) -> Result<usize, String> {
+        self.measure_content(bible, |content| content.chars().count())
+    }
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use crate::{bible_verse_organizer::BibleVerseOrganizer, book_segment::BookSegment, segments::BookPassageSegments};
+
+    fn john() -> BibleVerseOrganizer<String> {
+        let mut bible = BibleVerseOrganizer::<String>::new();
+        *bible.modify(BookSegment::chapter_verse(43, 1, 1)) = String::from("In the beginning was the Word, and the Word was with God, and the Word was God.");
+        *bible.modify(BookSegment::chapter_verse(43, 1, 2)) = String::from("He was in the beginning with God.");
+        *bible.modify(BookSegment::chapter_verse(43, 1, 3)) = String::from("All things were made through him, and without him was not any thing made that was made.");
+        bible
+    }
+
+    #[test]
+    fn verse_count() -> Result<(), String> {
+        assert_eq!(BookSegment::chapter_verse(43, 3, 16).verse_count()?, 1);
+        assert_eq!(BookSegment::full_chapter(43, 3).verse_count()?, 36);
+        assert_eq!(BookSegment::full_chapter_range(1, 1, 2).verse_count()?, 31 + 25);
+        assert_eq!(BookSegment::chapter_range(1, 1, 31, 2, 1).verse_count()?, 2);
+        assert!(BookSegment::full_chapter(43, 30).verse_count().is_err());
+
+        // John 3, 3:16, 4:1-3
+        assert_eq!(BookPassageSegments::parse(43, "3, 3:16, 4:1-3")?.verse_count()?, 36 + 3);
+        Ok(())
+    }
+
+    #[test]
+    fn chapter_count() -> Result<(), String> {
+        assert_eq!(BookSegment::chapter_verse(43, 3, 16).chapter_count()?, 1);
+        assert_eq!(BookSegment::chapter_range(1, 1, 31, 2, 1).chapter_count()?, 2);
+        assert_eq!(BookSegment::full_chapter_range(1, 1, 3).chapter_count()?, 3);
+
+        // John 1-2, 2:3, 5:1-3
+        assert_eq!(BookPassageSegments::parse(43, "1-2, 2:3, 5:1-3")?.chapter_count()?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn content() -> Result<(), String> {
+        let bible = john();
+
+        assert_eq!(BookSegment::chapter_verse(43, 1, 2).word_count(&bible), 7);
+        assert_eq!(BookSegment::chapter_verse(43, 1, 2).character_count(&bible), 33);
+        assert_eq!(BookSegment::full_chapter(43, 1).word_count(&bible), 17 + 7 + 17);
+
+        // verses are only counted once
+        assert_eq!(BookPassageSegments::parse(43, "1:1-2, 1:2")?.word_count(&bible)?, 17 + 7);
+
+        // a custom measure
+        assert_eq!(BookPassageSegments::parse(43, "1")?.measure_content(&bible, |content| content.matches("Word").count())?, 3);
+        Ok(())
+    }
+}
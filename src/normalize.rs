@@ -0,0 +1,226 @@
+use itertools::Itertools;
+
+use crate::{book_chapter_verse::{verse_count, BookChapterVerse}, book_segment::BookSegment, compare::SegmentCompare, passage_segments::{chapter_range::ChapterRange, full_chapter_range::FullChapterRange, range_pair::RangePair}, segment::PassageSegment, segments::{BookPassageSegments, PassageSegments}};
+
+/// - Sorts spans of global verse ids and joins the ones that overlap or are adjacent
+/// - Since the ids are global, `Genesis 1:31` and `Genesis 2:1` are adjacent
+pub(crate) fn merge_verse_ids(mut ids: Vec<RangePair<u16>>) -> Vec<RangePair<u16>> {
+    ids.sort();
+    let mut merged: Vec<RangePair<u16>> = Vec::with_capacity(ids.len());
+    for span in ids {
+        match merged.last_mut() {
+            Some(last) if span.start <= last.end + 1 => last.end = last.end.max(span.end),
+            _ => merged.push(span),
+        }
+    }
+    merged
+}
+
+/// - The most specific segment that covers every verse from `start` to `end`
+/// - Both verses must be in the same book
+fn segment_between(start: BookChapterVerse, end: BookChapterVerse) -> PassageSegment {
+    let is_full_chapters = start.verse() == 1
+        && verse_count(end.book(), end.chapter()) == Some(end.verse());
+    if is_full_chapters {
+        FullChapterRange::new(start.chapter(), end.chapter()).actual()
+    } else {
+        ChapterRange::new(start.chapter(), start.verse(), end.chapter(), end.verse()).actual()
+    }
+}
+
+/// - Converts a span of global verse ids back into the most specific segments that cover it
+/// - The span is split wherever it crosses into another book
+pub(crate) fn segments_from_verse_ids(ids: RangePair<u16>) -> Result<Vec<BookSegment<PassageSegment>>, String> {
+    let start = BookChapterVerse::from_verse(ids.start)?;
+    let end = BookChapterVerse::from_verse(ids.end)?;
+    (start.book()..=end.book()).map(|book| {
+        let first = if book == start.book() { start } else { BookChapterVerse::new(book, 1, 1)? };
+        let last = if book == end.book() { end } else { BookChapterVerse::last_of_book(book)? };
+        Ok(segment_between(first, last).with_book(book))
+    }).collect()
+}
+
+impl BookPassageSegments {
+    /// - Resolves every segment into the global verse ids it covers (see [`BookSegment::verse_ids`])
+    /// - The spans are sorted, and overlapping or adjacent spans are merged
+    pub(crate) fn merged_verse_ids(&self) -> Result<Vec<RangePair<u16>>, String> {
+        let ids: Vec<RangePair<u16>> = self.iter().map(|seg| seg.verse_ids()).try_collect()?;
+        Ok(merge_verse_ids(ids))
+    }
+
+    /// - Sorts the segments and merges the ones that overlap or are adjacent (even across chapters)
+    /// - Each merged segment becomes the most specific kind that describes it (`John 3:1-36` is `John 3`)
+    /// - This method validates the book/chapter/verse numbers
+    pub fn normalize(&self) -> Result<Self, String> {
+        Self::from_merged_verse_ids(self.book, self.merged_verse_ids()?)
+    }
+
+    /// - Builds the segments of a book from spans of global verse ids that are already merged (see [`merge_verse_ids`])
+    pub(crate) fn from_merged_verse_ids(book: u8, ids: Vec<RangePair<u16>>) -> Result<Self, String> {
+        let segments = ids.into_iter()
+            .map(segments_from_verse_ids)
+            .flatten_ok()
+            .map_ok(|seg| seg.segment)
+            .try_collect()?;
+        Ok(PassageSegments(segments).with_book(book))
+    }
+}
+
+impl BookPassageSegments {
+    /// - Describes any collection of verses with the fewest segments (the inverse of [`BookPassageSegments::verses`])
+    /// - There is one [`BookPassageSegments`] for each book, in the order of the books
+    /// - Verses can be in any order and may be repeated
+    pub fn from_verses(verses: impl IntoIterator<Item = BookChapterVerse>) -> Result<Vec<Self>, String> {
+        let ids = verses.into_iter().map(|verse| RangePair::from_point(verse.as_verse())).collect();
+        Self::from_merged_verse_ids_across_books(merge_verse_ids(ids))
+    }
+
+    /// - Like [`BookPassageSegments::from_merged_verse_ids`], but the spans may be in any book
+    /// - There is one [`BookPassageSegments`] for each book, in the order of the books
+    pub(crate) fn from_merged_verse_ids_across_books(ids: Vec<RangePair<u16>>) -> Result<Vec<Self>, String> {
+        let segments: Vec<BookSegment<PassageSegment>> = ids.into_iter()
+            .map(segments_from_verse_ids)
+            .flatten_ok()
+            .try_collect()?;
+        Ok(segments.into_iter()
+            .chunk_by(|seg| seg.book)
+            .into_iter()
+            .map(|(book, segments)| PassageSegments(segments.map(|seg| seg.segment).collect()).with_book(book))
+            .collect())
+    }
+}
+
+impl PassageSegments {
+    /// - See [`BookPassageSegments::normalize`]
+    /// - The book is required to know how many verses are in each chapter
+    pub fn normalize(&self, book: u8) -> Result<Self, String> {
+        Ok(self.clone().with_book(book).normalize()?.segments)
+    }
+}
+
+#[cfg(test)]
+mod normalize_tests {
+    use crate::{book_chapter_verse::BookChapterVerse, book_segment::BookSegment, passage_segments::range_pair::RangePair, segment::PassageSegment, segments::{BookPassageSegments, PassageSegments}};
+
+    use super::merge_verse_ids;
+
+    fn normalize(book: u8, input: &str) -> Vec<PassageSegment> {
+        PassageSegments::parse(input).unwrap().normalize(book).unwrap().0
+    }
+
+    fn normalize_segments(book: u8, segments: Vec<PassageSegment>) -> Vec<PassageSegment> {
+        PassageSegments(segments).normalize(book).unwrap().0
+    }
+
+    #[test]
+    fn merge() {
+        assert_eq!(
+            merge_verse_ids(vec![RangePair::new(5, 6), RangePair::new(1, 3), RangePair::new(2, 4)]),
+            vec![RangePair::new(1, 6)]
+        );
+
+        assert_eq!(
+            merge_verse_ids(vec![RangePair::new(1, 3), RangePair::new(5, 6)]),
+            vec![RangePair::new(1, 3), RangePair::new(5, 6)]
+        );
+    }
+
+    #[test]
+    fn overlapping_and_adjacent() {
+        // Genesis 1:1-3, 1:2-5, 1:6, 2
+        assert_eq!(normalize_segments(1, vec![
+            PassageSegment::chapter_verse_range(1, 1, 3),
+            PassageSegment::chapter_verse_range(1, 2, 5),
+            PassageSegment::chapter_verse(1, 6),
+            PassageSegment::full_chapter(2),
+        ]), vec![
+            PassageSegment::chapter_verse_range(1, 1, 6),
+            PassageSegment::full_chapter(2),
+        ]);
+
+        // Genesis 1:3, 1:1
+        assert_eq!(normalize(1, "1:3, 1:1"), vec![
+            PassageSegment::chapter_verse(1, 1),
+            PassageSegment::chapter_verse(1, 3),
+        ]);
+    }
+
+    #[test]
+    fn full_chapters() {
+        // John 3:1-36
+        assert_eq!(normalize(43, "3:1-36"), vec![
+            PassageSegment::full_chapter(3),
+        ]);
+
+        // Genesis 1:1-31, 2
+        assert_eq!(normalize_segments(1, vec![
+            PassageSegment::chapter_verse_range(1, 1, 31),
+            PassageSegment::full_chapter(2),
+        ]), vec![
+            PassageSegment::full_chapter_range(1, 2),
+        ]);
+
+        // Genesis 2-3, 1
+        assert_eq!(normalize(1, "2-3, 1"), vec![
+            PassageSegment::full_chapter_range(1, 3),
+        ]);
+    }
+
+    #[test]
+    fn across_chapters() {
+        // Genesis 1:31, 2:1
+        assert_eq!(normalize(1, "1:31, 2:1"), vec![
+            PassageSegment::chapter_range(1, 31, 2, 1),
+        ]);
+
+        // Genesis 1:20-2:3, 2:4-10
+        assert_eq!(normalize(1, "1:20-2:3, 2:4-10"), vec![
+            PassageSegment::chapter_range(1, 20, 2, 10),
+        ]);
+    }
+
+    #[test]
+    fn from_verses() -> Result<(), String> {
+        let verse = |book, chapter, verse| BookChapterVerse::new(book, chapter, verse).unwrap();
+
+        // John 3:18, 3:16, 3:17, 3:16
+        let passages = BookPassageSegments::from_verses([verse(43, 3, 18), verse(43, 3, 16), verse(43, 3, 17), verse(43, 3, 16)])?;
+        assert_eq!(passages.len(), 1);
+        assert_eq!(passages[0].book, 43);
+        assert_eq!(passages[0].segments.0, vec![PassageSegment::chapter_verse_range(3, 16, 18)]);
+
+        // John 3 and John 4:1
+        let passages = BookPassageSegments::from_verses(
+            BookSegment::full_chapter(43, 3).verses().chain([verse(43, 4, 1)])
+        )?;
+        assert_eq!(passages[0].segments.0, vec![PassageSegment::chapter_range(3, 1, 4, 1)]);
+
+        // Genesis 1-2, 3:5
+        let passages = BookPassageSegments::from_verses(
+            BookSegment::full_chapter_range(1, 1, 2).verses().chain([verse(1, 3, 5)])
+        )?;
+        assert_eq!(passages[0].segments.0, vec![PassageSegment::full_chapter_range(1, 2), PassageSegment::chapter_verse(3, 5)]);
+
+        // Genesis 50:26 and Exodus 1
+        let passages = BookPassageSegments::from_verses(
+            BookSegment::full_chapter(2, 1).verses().chain([verse(1, 50, 26)])
+        )?;
+        assert_eq!(passages.len(), 2);
+        assert_eq!((passages[0].book, passages[0].segments.0.clone()), (1, vec![PassageSegment::chapter_verse(50, 26)]));
+        assert_eq!((passages[1].book, passages[1].segments.0.clone()), (2, vec![PassageSegment::full_chapter(1)]));
+
+        assert!(BookPassageSegments::from_verses([])?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        // Jude has only 1 chapter
+        assert!(PassageSegments::parse("1-2").unwrap().normalize(65).is_err());
+        // Genesis 1 has only 31 verses
+        assert!(PassageSegments::parse("1:30-32").unwrap().normalize(1).is_err());
+        // ends before it starts
+        assert!(PassageSegments::parse("1:5-3").unwrap().normalize(1).is_err());
+    }
+}
@@ -20,8 +20,7 @@ static TRAILING_NON_DIGITS: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\D+$)").unw
 impl PassageSegments {
     pub fn parse(segment_input: &str) -> Result<Self, String> {
         let input = match_and_sanitize_segment_input(segment_input).ok_or_else(|| String::from("Failed to parse segments"))?;
-        let segments = parse_reference_segments(&input);
-        Ok(segments)
+        parse_reference_segments(&input)
     }
 }
 
@@ -99,6 +98,11 @@ impl FromStr for PassageSegment {
     }
 }
 
+/// - How many bytes at the start of `input` (the text right after a book name) look like reference segments
+pub(crate) fn segment_input_len(input: &str) -> Option<usize> {
+    POST_BOOK_VALID_REFERENCE_SEGMENT_CHARACTERS.find(input).map(|segment_match| segment_match.end())
+}
+
 fn match_and_sanitize_segment_input(segment_input: &str) -> Option<String> {
     let segment_match = POST_BOOK_VALID_REFERENCE_SEGMENT_CHARACTERS
         .find_iter(segment_input)
@@ -121,7 +125,8 @@ fn match_and_sanitize_segment_input(segment_input: &str) -> Option<String> {
 
 /// - This function is meant to parse the `1,2-4,5:1-3,5,7-9,12-6:6,7:7-8:8` in `John 1,2-4,5:1-3,5,7-9,12-6:6,7:7-8:8`
 /// - It expects input [`from match_and_sanitize_segment_input`]
-fn parse_reference_segments(input: &str) -> PassageSegments {
+/// - Numbers that are not chapters or verses (`300`, or the `17` in `3:16:17`) are an error rather than a panic
+fn parse_reference_segments(input: &str) -> Result<PassageSegments, String> {
     // split at , or ; (because there is no uniform standard)
     // now I only have ranges (or a single verse)
     let ranges: Vec<&str> = input.split(SEGMENT_SPLITTERS).collect();
@@ -139,8 +144,8 @@ fn parse_reference_segments(input: &str) -> PassageSegments {
             if check_for_full_chapters {
                 // try a chapter range
                 if !left.contains(":") && !right.contains(":") {
-                    let start = left.parse().unwrap();
-                    let end = right.parse().unwrap();
+                    let start = parse_number(left)?;
+                    let end = parse_number(right)?;
                     segments.push(PassageSegment::full_chapter_range(
                         start,
                         end,
@@ -154,40 +159,40 @@ fn parse_reference_segments(input: &str) -> PassageSegments {
             match (left.split_once(":"), right.split_once(":")) {
                 // `ch1:v1 - ch2:v2`
                 (Some((ch1, v1)), Some((ch2, v2))) => {
-                    chapter = ch2.parse().unwrap();
+                    chapter = parse_number(ch2)?;
                     segments.push(PassageSegment::chapter_range(
-                            ch1.parse().unwrap(),
-                            v1.parse().unwrap(),
+                            parse_number(ch1)?,
+                            parse_number(v1)?,
                             chapter,
-                            v2.parse().unwrap(),
+                            parse_number(v2)?,
                     ));
                 }
                 // `ch1:v1 - v2`
                 (Some((ch1, v1)), None) => {
-                    chapter = ch1.parse().unwrap();
+                    chapter = parse_number(ch1)?;
                     segments.push(PassageSegment::chapter_verse_range(
                         chapter,
-                        v1.parse().unwrap(),
-                        right.parse().unwrap(),
+                        parse_number(v1)?,
+                        parse_number(right)?,
                     ));
                 }
                 // `v1 - ch2:v2`
                 (None, Some((ch2, v2))) => {
                     let start_chapter = chapter;
-                    chapter = ch2.parse().unwrap();
+                    chapter = parse_number(ch2)?;
                     segments.push(PassageSegment::chapter_range(
                             start_chapter,
-                            left.parse().unwrap(),
+                            parse_number(left)?,
                             chapter,
-                            v2.parse().unwrap(),
+                            parse_number(v2)?,
                     ));
                 }
                 // `v1 - v2`
                 (None, None) => {
                     segments.push(PassageSegment::chapter_verse_range(
                         chapter,
-                        left.parse().unwrap(),
-                        right.parse().unwrap(),
+                        parse_number(left)?,
+                        parse_number(right)?,
                     ));
                 }
             };
@@ -196,23 +201,23 @@ fn parse_reference_segments(input: &str) -> PassageSegments {
         else {
             // handle `ch:v`
             if let Some((ch, v)) = range.split_once(":") {
-                chapter = ch.parse().unwrap();
+                chapter = parse_number(ch)?;
                 segments.push(PassageSegment::ChapterVerse(ChapterVerse {
                     chapter,
-                    verse: v.parse().unwrap(),
+                    verse: parse_number(v)?,
                 }))
             }
             // handle `ch` or `v`
             else {
                 // handle `ch`
                 if check_for_full_chapters {
-                    chapter = range.parse().unwrap();
+                    chapter = parse_number(range)?;
                     segments.push(PassageSegment::full_chapter(chapter));
                     continue;
                 }
 
                 // handle `v`
-                let v = range.parse().unwrap();
+                let v = parse_number(range)?;
                 segments.push(PassageSegment::ChapterVerse(ChapterVerse {
                     chapter,
                     verse: v,
@@ -221,7 +226,12 @@ fn parse_reference_segments(input: &str) -> PassageSegments {
             check_for_full_chapters = false;
         }
     }
-    PassageSegments(segments)
+    Ok(PassageSegments(segments))
+}
+
+/// - Chapters and verses must fit in a [`u8`]
+fn parse_number(input: &str) -> Result<u8, String> {
+    input.parse().map_err(|_| format!("'{}' is not a chapter or verse", input))
 }
 
 #[cfg(test)]
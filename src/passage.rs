@@ -1,238 +1,339 @@
-// use crate::{
-//     // api::BibleAPI,
-//     // formatter::{
-//     //     ChapterFormatParameters, PassageFormatParameters, PassageFormatter,
-//     //     SegmentFormatParameters, Template, VerseFormatParameters,
-//     // },
-//     segments::{PassageChapterRange, PassageSegment, PassageSegments},
-// };
-//
-// pub trait PassageDataProvider {
-//     fn get_book_name(&self, book: u8) -> String;
-//     fn get_content(&self, book: u8, chapter: u8, verse: u8) -> String;
-//     fn get_ending_chapter(&self, book: u8) -> u8;
-//     fn get_ending_verse(&self, book: u8, chapter: u8) -> u8;
-// }
-//
-// #[derive(Clone, Debug)]
-// pub struct Passage {
-//     // pub range: Range,
-//     pub book_id: u8,
-//     pub segments: PassageSegments,
-// }
-//
-// impl Passage {
-//     /// This should only be called after finding a match in a range
-//     // pub fn new(book_id: u8, range: Range, segment_input: &str) -> Self {
-//     //     // split into book name and segments
-//     //     // get book id
-//     //     let segments = PassageSegments::parse(segment_input);
-//     //     Self {
-//     //         range,
-//     //         book_id,
-//     //         segments,
-//     //     }
-//     // }
-//
-//     pub fn new(book_id: u8, segment_input: &str) -> Self {
-//         // split into book name and segments
-//         // get book id
-//         let segments = PassageSegments::parse(segment_input);
-//         Self { book_id, segments }
-//     }
-//
-//     // pub fn new_book(book_id: u8, api: &BibleAPI) -> Self {
-//     //     let end_chapter = api.data().get_book_chapter_count(book_id).unwrap_or(1);
-//     //     let end_verse = api
-//     //         .data()
-//     //         .get_chapter_verse_count(book_id, end_chapter)
-//     //         .unwrap_or(1);
-//     //     let segments = PassageSegments(vec![PassageSegment::PassageChapterRange(
-//     //         PassageChapterRange {
-//     //             start_chapter: 1,
-//     //             end_chapter,
-//     //             start_verse: 1,
-//     //             end_verse,
-//     //         },
-//     //     )]);
-//     //     Self { book_id, segments }
-//     // }
-//
-//     /// Formats into something like `Ephesians 1:1-4, 5-7, 2:2-3:4, 6`
-//     // pub fn full_ref_label(&self, api: &BibleAPI) -> String {
-//     //     let book_name = api
-//     //         .get_book_name(self.book_id)
-//     //         .expect("A Passage struct should not be created if the book_id is invalid.");
-//     //     format!("{} {}", book_name, self.segments.label())
-//     // }
-//
-//     /**
-//     Returns text like the following:
-//
-//     ```text
-//     [1:1] Paul, an apostle of Christ Jesus by the will of God, To the saints who are in Ephesus, and are faithful in Christ Jesus:
-//     [1:2] Grace to you and peace from God our Father and the Lord Jesus Christ.
-//     [1:3] Blessed be the God and Father of our Lord Jesus Christ, who has blessed us in Christ with every spiritual blessing in the heavenly places,
-//     [1:4] even as he chose us in him before the foundation of the world, that we should be holy and blameless before him. In love
-//     ```
-//     */
-//     // pub fn format(&self, api: &BibleAPI, formatter: &PassageFormatter) -> String {
-//     //     // let formatter = api.formatter();
-//     //     // let book = "Ephesians";
-//     //     // im pretty sure its guaranteed that if it parsed it is a valid book id
-//     //     let book = &api.data().get_book_name(self.book_id).unwrap();
-//     //     // let segment_template = Template::from_template("{verses}").unwrap();
-//     //     // let chapter_template = Template::from_template("\n[{chapter}] {verses}").unwrap();
-//     //     // let verse_template = Template::from_template("[{chapter}:{verse}] {content}").unwrap();
-//     //
-//     //     // i could cache these better
-//     //     let segment_template = Template::from_template(&formatter.segment).unwrap();
-//     //     let chapter_template = Template::from_template(&formatter.chapter).unwrap();
-//     //     let verse_template = Template::from_template(&formatter.verse).unwrap();
-//     //     let passage_template = Template::from_template(&formatter.text).unwrap();
-//     //
-//     //     let segment_range_content = self
-//     //         .segments
-//     //         .iter()
-//     //         .map(|seg| {
-//     //             let chapter_range_content = (seg.get_starting_chapter()..=seg.get_ending_chapter())
-//     //                 .map(|chapter| {
-//     //                     let start_verse = if chapter == seg.get_starting_chapter() {
-//     //                         seg.get_starting_verse()
-//     //                     } else {
-//     //                         1
-//     //                     };
-//     //                     let end_verse = if chapter == seg.get_ending_chapter() {
-//     //                         seg.get_ending_verse()
-//     //                     } else {
-//     //                         api.data()
-//     //                             .get_chapter_verse_count(self.book_id, chapter)
-//     //                             .unwrap_or(0)
-//     //                     };
-//     //                     let verse_range_content = (start_verse..=end_verse)
-//     //                         .filter_map(|verse| {
-//     //                             let content =
-//     //                                 api.data()
-//     //                                     .get_bible_contents(self.book_id, chapter, verse)?;
-//     //
-//     //                             let params = VerseFormatParameters {
-//     //                                 book,
-//     //                                 chapter,
-//     //                                 verse,
-//     //                                 // content: &format!("content-{chapter}-{verse}"),
-//     //                                 content: &content,
-//     //                             };
-//     //                             Some(verse_template.fill(&params).unwrap())
-//     //                         })
-//     //                         .collect::<Vec<_>>()
-//     //                         .join(&formatter.join_verses);
-//     //
-//     //                     let params = ChapterFormatParameters {
-//     //                         book,
-//     //                         chapter,
-//     //                         start_verse,
-//     //                         end_verse,
-//     //                         verses: &verse_range_content,
-//     //                     };
-//     //                     chapter_template.fill(&params).unwrap()
-//     //                 })
-//     //                 .collect::<Vec<_>>()
-//     //                 // this will not work how you expect
-//     //                 // because segments are outside of chapters
-//     //                 .join(&formatter.join_chapters);
-//     //             // format!("{} {chapter_range_content}", seg.label())
-//     //             let params = SegmentFormatParameters {
-//     //                 book,
-//     //                 label: &seg.label(),
-//     //                 verses: &chapter_range_content,
-//     //             };
-//     //             segment_template.fill(&params).unwrap()
-//     //         })
-//     //         .collect::<Vec<_>>()
-//     //         .join(&formatter.join_segments);
-//     //
-//     //     let params = PassageFormatParameters {
-//     //         book,
-//     //         segments: &segment_range_content,
-//     //         label: &self.segments.label(),
-//     //     };
-//     //     passage_template.fill(&params).unwrap()
-//     // }
-//
-//     // pub fn format_closure(
-//     //     &self,
-//     //     formatter: PassageFormatter,
-//     //     book: &str,
-//     //     get_content: impl Fn(u8, u8) -> String,
-//     // ) -> String {
-//     //     // let book = "Ephesians";
-//     //     // let segment_template = Template::from_template("{verses}").unwrap();
-//     //     // let chapter_template = Template::from_template("\n[{chapter}] {verses}").unwrap();
-//     //     // let verse_template = Template::from_template("[{chapter}:{verse}] {content}").unwrap();
-//     //
-//     //     let segment_template = Template::from_template(&formatter.segment).unwrap();
-//     //     let chapter_template = Template::from_template(&formatter.chapter).unwrap();
-//     //     let verse_template = Template::from_template(&formatter.verse).unwrap();
-//     //     let passage_template = Template::from_template(&formatter.text).unwrap();
-//     //
-//     //     let segment_range_content = self
-//     //         .segments
-//     //         .iter()
-//     //         .map(|seg| {
-//     //             let chapter_range_content = (seg.get_starting_chapter()..=seg.get_ending_chapter())
-//     //                 .map(|chapter| {
-//     //                     let start_verse = if chapter == seg.get_starting_chapter() {
-//     //                         seg.get_starting_verse()
-//     //                     } else {
-//     //                         1
-//     //                     };
-//     //                     let end_verse = if chapter == seg.get_ending_chapter() {
-//     //                         seg.get_ending_verse()
-//     //                     } else {
-//     //                         10 // calc
-//     //                     };
-//     //                     let verse_range_content = (start_verse..=end_verse)
-//     //                         .map(|verse| {
-//     //                             let params = VerseFormatParameters {
-//     //                                 book,
-//     //                                 chapter,
-//     //                                 verse,
-//     //                                 content: &get_content(chapter, verse),
-//     //                             };
-//     //                             verse_template.fill(&params).unwrap()
-//     //                         })
-//     //                         .collect::<Vec<_>>()
-//     //                         .join(&formatter.join_verses);
-//     //
-//     //                     let params = ChapterFormatParameters {
-//     //                         book,
-//     //                         chapter,
-//     //                         start_verse,
-//     //                         end_verse,
-//     //                         verses: &verse_range_content,
-//     //                     };
-//     //                     chapter_template.fill(&params).unwrap()
-//     //                 })
-//     //                 .collect::<Vec<_>>()
-//     //                 // this will not work how you expect
-//     //                 // because segments are outside of chapters
-//     //                 .join(&formatter.join_chapters);
-//     //             // format!("{} {chapter_range_content}", seg.label())
-//     //             let params = SegmentFormatParameters {
-//     //                 book,
-//     //                 label: &seg.label(),
-//     //                 verses: &chapter_range_content,
-//     //             };
-//     //             segment_template.fill(&params).unwrap()
-//     //         })
-//     //         .collect::<Vec<_>>()
-//     //         .join(&formatter.join_segments);
-//     //
-//     //     let params = PassageFormatParameters {
-//     //         book,
-//     //         segments: &segment_range_content,
-//     //         label: &self.segments.label(),
-//     //     };
-//     //     passage_template.fill(&params).unwrap()
-//     // }
-// }
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use itertools::Itertools;
+
+use crate::{bible_verse_organizer::BibleVerseOrganizer, book_manager::BookManager, compare::SegmentCompare, segments::BookPassageSegments};
+
+/// - A value that can be put into a [`Template`] with `{name}`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Placeholder {
+    /// - `{book}`: the book name from the [`BookManager`]
+    Book,
+    /// - `{chapter}`
+    Chapter,
+    /// - `{verse}`
+    Verse,
+    /// - `{content}`: the verse content, or the joined content of the level below
+    Content,
+    /// - `{label}`: the reference being rendered, like `3:16` or `3:16, 18; 4:1`
+    Label,
+}
+
+/// - The placeholders that one kind of [`Template`] can use, like [`Placeholder`] or [`LinkPlaceholder`](crate::link::LinkPlaceholder)
+pub trait TemplatePlaceholder: Copy + Eq + FromStr<Err = String> {
+    /// - The name between the braces, like `chapter` for `{chapter}`
+    fn name(&self) -> &'static str;
+}
+
+impl TemplatePlaceholder for Placeholder {
+    fn name(&self) -> &'static str {
+        match self {
+            Placeholder::Book => "book",
+            Placeholder::Chapter => "chapter",
+            Placeholder::Verse => "verse",
+            Placeholder::Content => "content",
+            Placeholder::Label => "label",
+        }
+    }
+}
+
+impl FromStr for Placeholder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "book" => Placeholder::Book,
+            "chapter" => Placeholder::Chapter,
+            "verse" => Placeholder::Verse,
+            "content" => Placeholder::Content,
+            "label" => Placeholder::Label,
+            _ => Err(format!("Unknown placeholder '{{{}}}'", s))?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TemplatePart<P> {
+    Text(String),
+    Placeholder(P),
+}
+
+/// - Text with placeholders, like `[{chapter}:{verse}] {content}`
+/// - Use `{{` and `}}` for literal braces
+/// - The kind of placeholder decides which names parse, so a render template can not use `{osis}`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template<P: TemplatePlaceholder = Placeholder> {
+    parts: Vec<TemplatePart<P>>,
+}
+
+impl<P: TemplatePlaceholder> Template<P> {
+    /// - Returns an error for unknown placeholders and unmatched braces
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => Err(format!("Unclosed '{{' in template '{}'", input))?,
+                        }
+                    }
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(TemplatePart::Placeholder(name.trim().parse()?));
+                }
+                '}' => Err(format!("Unmatched '}}' in template '{}'", input))?,
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Self { parts })
+    }
+
+    pub fn placeholders(&self) -> impl Iterator<Item = P> + '_ {
+        self.parts.iter().filter_map(|part| match part {
+            TemplatePart::Placeholder(placeholder) => Some(*placeholder),
+            TemplatePart::Text(_) => None,
+        })
+    }
+
+    /// - Replaces every placeholder with its value
+    /// - Placeholders without a value are left empty
+    pub fn fill(&self, value: impl Fn(P) -> Option<String>) -> String {
+        self.parts.iter().map(|part| match part {
+            TemplatePart::Text(text) => text.clone(),
+            TemplatePart::Placeholder(placeholder) => value(*placeholder).unwrap_or_default(),
+        }).collect()
+    }
+
+    /// - Returns an error if this template uses a placeholder that is not allowed
+    pub(crate) fn check(&self, level: &str, allowed: &[P]) -> Result<(), String> {
+        match self.placeholders().find(|placeholder| !allowed.contains(placeholder)) {
+            Some(placeholder) => Err(format!("The {} template can not use '{{{}}}'", level, placeholder.name())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<P: TemplatePlaceholder> FromStr for Template<P> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<P: TemplatePlaceholder> Display for Template<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for part in self.parts.iter() {
+            match part {
+                TemplatePart::Text(text) => write!(f, "{}", text.replace('{', "{{").replace('}', "}}"))?,
+                TemplatePart::Placeholder(placeholder) => write!(f, "{{{}}}", placeholder.name())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// - How [`BookPassageSegments::render`] turns verse content into text
+/// - Each level fills its `{content}` with the joined results of the level below: passage <- segment <- chapter <- verse
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PassageFormatter {
+    /// - `{book}`, `{label}` (the whole passage), and `{content}` (the joined segments)
+    pub passage: Template,
+    /// - `{book}`, `{label}` (this segment), and `{content}` (the joined chapters)
+    pub segment: Template,
+    /// - `{book}`, `{chapter}`, and `{content}` (the joined verses)
+    pub chapter: Template,
+    /// - `{book}`, `{chapter}`, `{verse}`, `{label}` (like `3:16`), and `{content}` (the verse content)
+    pub verse: Template,
+    pub join_verses: String,
+    pub join_chapters: String,
+    pub join_segments: String,
+}
+
+impl PassageFormatter {
+    const PASSAGE_PLACEHOLDERS: [Placeholder; 3] = [Placeholder::Book, Placeholder::Label, Placeholder::Content];
+    const SEGMENT_PLACEHOLDERS: [Placeholder; 3] = [Placeholder::Book, Placeholder::Label, Placeholder::Content];
+    const CHAPTER_PLACEHOLDERS: [Placeholder; 3] = [Placeholder::Book, Placeholder::Chapter, Placeholder::Content];
+    const VERSE_PLACEHOLDERS: [Placeholder; 5] = [Placeholder::Book, Placeholder::Chapter, Placeholder::Verse, Placeholder::Label, Placeholder::Content];
+
+    /// - Joins verses, chapters, and segments with new lines (see [`Default`])
+    /// - Returns an error if a template does not parse or uses a placeholder its level does not have
+    pub fn new(passage: &str, segment: &str, chapter: &str, verse: &str) -> Result<Self, String> {
+        let formatter = Self {
+            passage: passage.parse()?,
+            segment: segment.parse()?,
+            chapter: chapter.parse()?,
+            verse: verse.parse()?,
+            ..Default::default()
+        };
+        formatter.passage.check("passage", &Self::PASSAGE_PLACEHOLDERS)?;
+        formatter.segment.check("segment", &Self::SEGMENT_PLACEHOLDERS)?;
+        formatter.chapter.check("chapter", &Self::CHAPTER_PLACEHOLDERS)?;
+        formatter.verse.check("verse", &Self::VERSE_PLACEHOLDERS)?;
+        Ok(formatter)
+    }
+
+    pub fn with_joiners(mut self, verses: &str, chapters: &str, segments: &str) -> Self {
+        self.join_verses = verses.to_string();
+        self.join_chapters = chapters.to_string();
+        self.join_segments = segments.to_string();
+        self
+    }
+}
+
+/// - Renders text like the following:
+///
+/// ```text
+/// [1:1] Paul, an apostle of Christ Jesus by the will of God, To the saints who are in Ephesus, and are faithful in Christ Jesus:
+/// [1:2] Grace to you and peace from God our Father and the Lord Jesus Christ.
+/// ```
+impl Default for PassageFormatter {
+    fn default() -> Self {
+        Self {
+            passage: Template { parts: vec![TemplatePart::Placeholder(Placeholder::Content)] },
+            segment: Template { parts: vec![TemplatePart::Placeholder(Placeholder::Content)] },
+            chapter: Template { parts: vec![TemplatePart::Placeholder(Placeholder::Content)] },
+            verse: Template::parse("[{chapter}:{verse}] {content}").unwrap(),
+            join_verses: String::from("\n"),
+            join_chapters: String::from("\n"),
+            join_segments: String::from("\n"),
+        }
+    }
+}
+
+impl BookPassageSegments {
+    /// - Renders the content of every verse in this passage with the templates of the [`PassageFormatter`]
+    /// - Segments are rendered in order, without [`normalize`](BookPassageSegments::normalize)
+    /// - Verses without content are skipped, and so are chapters without any
+    /// - Returns an error if the [`BookManager`] does not know the book
+    pub fn render<Content: Debug + Default + Display>(&self, manager: &BookManager, bible: &BibleVerseOrganizer<Content>, formatter: &PassageFormatter) -> Result<String, String> {
+        let book = manager.book_id_to_name.get(&self.book).ok_or_else(|| format!("There is no 'Book {}' in the Bible", self.book))?;
+
+        let segments = self.segments.iter().map(|seg| {
+            let key = seg.with_book(self.book);
+            let chapters = bible.iter_segment_content(&key)
+                .chunk_by(|psg| psg.segment.chapter)
+                .into_iter()
+                .map(|(chapter, verses)| {
+                    let verses = verses.map(|psg| formatter.verse.fill(|placeholder| match placeholder {
+                        Placeholder::Book => Some(book.clone()),
+                        Placeholder::Chapter => Some(chapter.to_string()),
+                        Placeholder::Verse => Some(psg.segment.verse.to_string()),
+                        Placeholder::Label => Some(psg.segment.to_string()),
+                        Placeholder::Content => Some(psg.content.to_string()),
+                    })).join(&formatter.join_verses);
+                    formatter.chapter.fill(|placeholder| match placeholder {
+                        Placeholder::Book => Some(book.clone()),
+                        Placeholder::Chapter => Some(chapter.to_string()),
+                        Placeholder::Content => Some(verses.clone()),
+                        Placeholder::Verse | Placeholder::Label => None,
+                    })
+                })
+                .join(&formatter.join_chapters);
+            formatter.segment.fill(|placeholder| match placeholder {
+                Placeholder::Book => Some(book.clone()),
+                Placeholder::Label => Some(seg.to_string()),
+                Placeholder::Content => Some(chapters.clone()),
+                Placeholder::Chapter | Placeholder::Verse => None,
+            })
+        }).join(&formatter.join_segments);
+
+        Ok(formatter.passage.fill(|placeholder| match placeholder {
+            Placeholder::Book => Some(book.clone()),
+            Placeholder::Label => Some(self.label()),
+            Placeholder::Content => Some(segments.clone()),
+            Placeholder::Chapter | Placeholder::Verse => None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod passage_tests {
+    use crate::{bible_verse_organizer::BibleVerseOrganizer, book_manager::DEFAULT_BOOK_MANAGER, book_segment::BookSegment, segments::BookPassageSegments};
+
+    use super::{PassageFormatter, Placeholder, Template};
+
+    fn john() -> BibleVerseOrganizer<String> {
+        let mut bible = BibleVerseOrganizer::<String>::new();
+        for (chapter, verse) in [(3, 16), (3, 17), (3, 18), (4, 1), (4, 2)] {
+            *bible.modify(BookSegment::chapter_verse(43, chapter, verse)) = format!("content-{}-{}", chapter, verse);
+        }
+        bible
+    }
+
+    fn render(input: &str, formatter: &PassageFormatter) -> String {
+        BookPassageSegments::parse(43, input).unwrap().render(&DEFAULT_BOOK_MANAGER, &john(), formatter).unwrap()
+    }
+
+    #[test]
+    fn templates() {
+        let template = Template::parse("[{chapter}:{verse}] {content}").unwrap();
+        assert_eq!(template.placeholders().collect::<Vec<_>>(), vec![Placeholder::Chapter, Placeholder::Verse, Placeholder::Content]);
+        let filled = template.fill(|placeholder| match placeholder {
+            Placeholder::Chapter => Some(String::from("3")),
+            Placeholder::Verse => Some(String::from("16")),
+            _ => None,
+        });
+        assert_eq!(filled, "[3:16] ");
+
+        assert_eq!(Template::<Placeholder>::parse("{{{label}}}").unwrap().to_string(), "{{{label}}}");
+        assert!(Template::<Placeholder>::parse("{unknown}").is_err());
+        assert!(Template::<Placeholder>::parse("{content").is_err());
+        assert!(Template::<Placeholder>::parse("content}").is_err());
+        // link placeholders can not be rendered
+        assert!(Template::<Placeholder>::parse("{osis}").is_err());
+        assert!(PassageFormatter::new("{content}", "{content}", "{content}", "{end_verse}").is_err());
+    }
+
+    #[test]
+    fn default_formatter() {
+        assert_eq!(
+            render("3:16-4:1", &PassageFormatter::default()),
+            "[3:16] content-3-16\n[3:17] content-3-17\n[3:18] content-3-18\n[4:1] content-4-1"
+        );
+        // verses without content are skipped
+        assert_eq!(render("3:18-20", &PassageFormatter::default()), "[3:18] content-3-18");
+    }
+
+    #[test]
+    fn custom_formatter() {
+        let formatter = PassageFormatter::new("# {book} {label}\n{content}", "## {label}\n{content}", "{chapter}. {content}", "<sup>{verse}</sup> {content}")
+            .unwrap()
+            .with_joiners(" ", "\n", "\n\n");
+        assert_eq!(
+            render("3:16-17, 3:18-4:2", &formatter),
+            "# John 3:16-17, 18-4:2\n## 3:16-17\n3. <sup>16</sup> content-3-16 <sup>17</sup> content-3-17\n\n## 3:18-4:2\n3. <sup>18</sup> content-3-18\n4. <sup>1</sup> content-4-1 <sup>2</sup> content-4-2"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(PassageFormatter::new("{content}", "{content}", "{verse}", "{content}").is_err());
+        assert!(PassageFormatter::new("{chapter}", "{content}", "{content}", "{content}").is_err());
+        assert!(PassageFormatter::new("{content}", "{content}", "{content}", "{label}").is_ok());
+
+        let passage = BookPassageSegments::parse(67, "1").unwrap();
+        assert!(passage.render(&DEFAULT_BOOK_MANAGER, &john(), &PassageFormatter::default()).is_err());
+    }
+}
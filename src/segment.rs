@@ -23,7 +23,7 @@ use crate::{compare::SegmentCompare, passage_segments::{chapter_range::ChapterRa
 /// Chapter Range:       `7:7-8:8`                          |
 /// --------------------------------------------------------+
 /// ```
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PassageSegment {
     /// - This is a single chapter/verse reference
@@ -43,14 +43,31 @@ pub enum PassageSegment {
     FullChapterRange(FullChapterRange),
 }
 
+impl PassageSegment {
+    /// - Only used to break ties between segments that cover the same chapters/verses
+    fn variant_order(&self) -> u8 {
+        match self {
+            PassageSegment::ChapterVerse(_) => 0,
+            PassageSegment::ChapterVerseRange(_) => 1,
+            PassageSegment::ChapterRange(_) => 2,
+            PassageSegment::FullChapter(_) => 3,
+            PassageSegment::FullChapterRange(_) => 4,
+        }
+    }
+}
+
+/// - Ordered by where the segments start, then by where they end
+/// - Full chapters end after every verse in their last chapter, so `John 3:1-20` comes before `John 3`
+impl Ord for PassageSegment {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.chapter_verse_bounds().cmp(&other.chapter_verse_bounds())
+            .then(self.variant_order().cmp(&other.variant_order()))
+    }
+}
+
 impl PartialOrd for PassageSegment {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(
-            self.starting_chapter().cmp(&other.starting_chapter())
-            .then(self.starting_verse().cmp(&other.starting_verse()))
-            .then(self.ending_chapter().cmp(&other.ending_chapter()))
-            .then(self.ending_verse().cmp(&other.ending_verse()))
-        )
+        Some(self.cmp(other))
     }
 }
 
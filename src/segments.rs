@@ -3,9 +3,11 @@ use std::{fmt::Debug, iter::Map, ops::{Deref, DerefMut}, slice::Iter, vec::IntoI
 use derive_more::IntoIterator;
 use serde::{Deserialize, Serialize};
 
-use crate::{book_segment::BookSegment, compare::SegmentCompare, segment::PassageSegment};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use crate::{book_chapter_verse::BookChapterVerse, book_segment::{verse_id_distance, BookSegment}, compare::SegmentCompare, passage_segments::range_pair::RangePair, segment::PassageSegment};
+
+/// - Ordered by book, then by the segments (see [`PassageSegments`]'s ordering)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct BookPassageSegments {
     pub book: u8,
     pub segments: PassageSegments,
@@ -55,9 +57,38 @@ impl BookPassageSegments {
         if self.book != other.book { return false; }
         self.segments.contains_overlap(&other.segments)
     }
+
+    /// - See [`PassageSegments::overlapping_pairs`]
+    /// - Passages in different books have no overlapping pairs
+    pub fn overlapping_pairs(&self, other: &BookPassageSegments) -> Vec<(usize, usize)> {
+        if self.book != other.book { return vec![]; }
+        self.segments.overlapping_pairs(&other.segments)
+    }
+
+    /// - The fewest verses between any segment of this passage and any segment of the other (see [`BookSegment::verse_distance`])
+    /// - This method validates the book/chapter/verse numbers
+    /// - The merged spans are sorted, so this sweeps through both sides together instead of comparing every pair
+    pub fn verse_distance(&self, other: &BookPassageSegments) -> Result<u16, String> {
+        let (this, other) = (self.merged_verse_ids()?, other.merged_verse_ids()?);
+        let mut closest = None;
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (this.get(i), other.get(j)) {
+            let distance = verse_id_distance(*a, *b);
+            closest = Some(closest.map_or(distance, |closest: u16| closest.min(distance)));
+            // the span that ends first can not be closer to anything after the other one
+            if a.end <= b.end { i += 1; } else { j += 1; }
+        }
+        closest.ok_or_else(|| String::from("Cannot measure the distance to an empty passage"))
+    }
+
+    /// - Every verse of every segment, in the order of the segments (see [`BookSegment::verses`])
+    /// - Verses are repeated when segments overlap, so [`normalize`](BookPassageSegments::normalize) first to get each verse once
+    pub fn verses(&self) -> impl Iterator<Item = BookChapterVerse> + '_ {
+        self.iter().flat_map(|seg| seg.verses())
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[derive(IntoIterator)]
 pub struct PassageSegments(pub Vec<PassageSegment>);
 
@@ -75,6 +106,31 @@ impl DerefMut for PassageSegments {
     }
 }
 
+/// - Ordered by where the earliest segment starts, then by where the latest segment ends
+/// - Ties are broken by comparing the segments one by one
+/// - Empty passages come first
+impl Ord for PassageSegments {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.chapter_verse_bounds().cmp(&other.chapter_verse_bounds())
+            .then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for PassageSegments {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PassageSegments {
+    /// - See [`SegmentCompare::chapter_verse_bounds`]
+    fn chapter_verse_bounds(&self) -> Option<RangePair<(u8, u8)>> {
+        let start = self.iter().map(|seg| seg.chapter_verse_bounds().start).min()?;
+        let end = self.iter().map(|seg| seg.chapter_verse_bounds().end).max()?;
+        Some(RangePair::new(start, end))
+    }
+}
+
 impl PassageSegments {
     pub fn new() -> Self {
         Self(vec![])
@@ -92,16 +148,72 @@ impl PassageSegments {
         self.iter().any(|this| this.overlaps_with(other))
     }
 
-    /// - This can be better optimized, but that is not a priority right now
-    /// - I just need some way to order the segments and do it in linear time
+    /// - Indices of the segments, sorted by where they start (see [`SegmentCompare::chapter_verse_bounds`])
+    fn sorted_bounds(&self) -> Vec<(usize, RangePair<(u8, u8)>)> {
+        let mut bounds: Vec<_> = self.iter().map(|seg| seg.chapter_verse_bounds()).enumerate().collect();
+        bounds.sort_by_key(|&(_, bounds)| bounds.start);
+        bounds
+    }
+
+    /// - Sorts both sides, then sweeps through them together, so this is `O(n log n + m log m)`
     pub fn contains_overlap(&self, other: &PassageSegments) -> bool {
-        self.iter().any(|this| other.overlaps_with(this))
+        let (this, other) = (self.sorted_bounds(), other.sorted_bounds());
+        let (mut i, mut j) = (0, 0);
+        while let (Some((_, a)), Some((_, b))) = (this.get(i), other.get(j)) {
+            // whichever ends before the other starts cannot overlap with anything after the other either
+            if a.end < b.start {
+                i += 1;
+            } else if b.end < a.start {
+                j += 1;
+            } else {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// - Every `(index in self, index in other)` where the segments overlap (see [`SegmentCompare::overlaps_with`])
+    /// - The pairs are sorted
+    /// - Sorts both sides, then sweeps through them together, so this is `O(n log n + m log m + pairs)`
+    pub fn overlapping_pairs(&self, other: &PassageSegments) -> Vec<(usize, usize)> {
+        let (this, other) = (self.sorted_bounds(), other.sorted_bounds());
+        let mut pairs = Vec::new();
+        // segments that started already and may still overlap with the next ones
+        let mut active_this: Vec<(usize, RangePair<(u8, u8)>)> = Vec::new();
+        let mut active_other: Vec<(usize, RangePair<(u8, u8)>)> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        loop {
+            let next_is_this = match (this.get(i), other.get(j)) {
+                (Some((_, a)), Some((_, b))) => a.start <= b.start,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if next_is_this {
+                let (idx, bounds) = this[i];
+                // everything still active started before this one, so it overlaps if it has not ended
+                active_other.retain(|(_, active)| active.end >= bounds.start);
+                pairs.extend(active_other.iter().map(|&(other_idx, _)| (idx, other_idx)));
+                active_this.push((idx, bounds));
+                i += 1;
+            } else {
+                let (idx, bounds) = other[j];
+                active_this.retain(|(_, active)| active.end >= bounds.start);
+                pairs.extend(active_this.iter().map(|&(this_idx, _)| (this_idx, idx)));
+                active_other.push((idx, bounds));
+                j += 1;
+            }
+        }
+        pairs.sort();
+        pairs
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::BookPassageSegments;
+    use crate::{compare::SegmentCompare, segment::PassageSegment};
+
+    use super::{BookPassageSegments, PassageSegments};
 
     #[test]
     fn test() {
@@ -111,4 +223,108 @@ mod tests {
             println!("{seg:#?}");
         }
     }
+
+    #[test]
+    fn verses() {
+        // John 3:16-17, 4:1
+        let segs = BookPassageSegments::parse(43, "3:16-17, 4:1").unwrap();
+        assert_eq!(
+            segs.verses().map(|verse| verse.as_id_string()).collect::<Vec<_>>(),
+            vec!["43003016", "43003017", "43004001"]
+        );
+
+        // John 3, 4:1-3
+        let segs = BookPassageSegments::parse(43, "3, 4:1-3").unwrap();
+        assert_eq!(segs.verses().count(), 36 + 3);
+    }
+
+    #[test]
+    fn order() {
+        let mut passages = vec![
+            BookPassageSegments::parse(43, "3:16").unwrap(),
+            BookPassageSegments::parse(1, "2").unwrap(),
+            BookPassageSegments::parse(43, "3:1-16").unwrap(),
+            BookPassageSegments::parse(43, "3").unwrap(),
+            BookPassageSegments::parse(43, "4:1, 2:1").unwrap(),
+        ];
+        passages.sort();
+        assert_eq!(passages, vec![
+            BookPassageSegments::parse(1, "2").unwrap(),
+            BookPassageSegments::parse(43, "4:1, 2:1").unwrap(),
+            BookPassageSegments::parse(43, "3:1-16").unwrap(),
+            BookPassageSegments::parse(43, "3").unwrap(),
+            BookPassageSegments::parse(43, "3:16").unwrap(),
+        ]);
+    }
+
+    /// - The pairs the straightforward way, to check the sweep against
+    fn brute_force_pairs(this: &PassageSegments, other: &PassageSegments) -> Vec<(usize, usize)> {
+        let mut pairs = vec![];
+        for (i, a) in this.iter().enumerate() {
+            for (j, b) in other.iter().enumerate() {
+                if a.overlaps_with(b) { pairs.push((i, j)); }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn overlapping_pairs() {
+        let this = PassageSegments::parse("1, 3:16, 4:1-10, 3:1-5, 5:1-6:2").unwrap();
+        let other = PassageSegments::parse("3:4-17, 7, 4:10-5:1, 1:30").unwrap();
+        assert_eq!(this.overlapping_pairs(&other), vec![(0, 3), (1, 0), (2, 2), (3, 0), (4, 2)]);
+        assert_eq!(this.overlapping_pairs(&other), brute_force_pairs(&this, &other));
+        assert!(this.contains_overlap(&other));
+
+        let other = PassageSegments::parse("2, 3:6-15, 8").unwrap();
+        assert!(this.overlapping_pairs(&other).is_empty());
+        assert!(!this.contains_overlap(&other));
+        assert!(!this.contains_overlap(&PassageSegments::new()));
+
+        // different books never overlap
+        let this = BookPassageSegments::parse(43, "3").unwrap();
+        assert_eq!(this.overlapping_pairs(&BookPassageSegments::parse(43, "3:16").unwrap()), vec![(0, 0)]);
+        assert!(this.overlapping_pairs(&BookPassageSegments::parse(42, "3:16").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn overlapping_pairs_match_brute_force() {
+        // many segments that overlap each other in different ways
+        let make = |seed: u8| PassageSegments((0..40u8).map(|n| {
+            let chapter = (n.wrapping_mul(seed) % 6) + 1;
+            let verse = (n.wrapping_mul(seed.wrapping_add(3)) % 20) + 1;
+            match n % 4 {
+                0 => PassageSegment::chapter_verse(chapter, verse),
+                1 => PassageSegment::chapter_verse_range(chapter, verse, verse + n % 7),
+                2 => PassageSegment::chapter_range(chapter, verse, chapter + 1, verse),
+                _ => PassageSegment::full_chapter(chapter),
+            }
+        }).collect());
+        for (a, b) in [(7, 11), (13, 5), (3, 3)] {
+            let (this, other) = (make(a), make(b));
+            assert_eq!(this.overlapping_pairs(&other), brute_force_pairs(&this, &other));
+            assert_eq!(this.contains_overlap(&other), !brute_force_pairs(&this, &other).is_empty());
+        }
+    }
+
+    #[test]
+    fn verse_distance() {
+        // John 3:1-5, 3:20 and John 3:16-17
+        let this = BookPassageSegments::parse(43, "3:1-5, 3:20").unwrap();
+        let other = BookPassageSegments::parse(43, "3:16-17").unwrap();
+        assert_eq!(this.verse_distance(&other), Ok(3));
+
+        // the closest spans are the last ones, and either side can be given first
+        let this = BookPassageSegments::parse(43, "3:30, 3:1-5, 3:20").unwrap();
+        let other = BookPassageSegments::parse(43, "3:10, 3:25-26").unwrap();
+        assert_eq!(this.verse_distance(&other), Ok(4));
+        assert_eq!(other.verse_distance(&this), Ok(4));
+
+        // Genesis 50 and Exodus 1:2
+        let this = BookPassageSegments::parse(1, "50").unwrap();
+        let other = BookPassageSegments::parse(2, "1:2").unwrap();
+        assert_eq!(this.verse_distance(&other), Ok(2));
+
+        assert!(this.verse_distance(&PassageSegments::new().with_book(2)).is_err());
+    }
 }
@@ -0,0 +1,229 @@
+use crate::{normalize::merge_verse_ids, passage_segments::range_pair::RangePair, segments::{BookPassageSegments, PassageSegments}};
+
+// These all expect spans of global verse ids that are sorted and merged (see [`merge_verse_ids`])
+
+pub(crate) fn union_verse_ids(this: &[RangePair<u16>], other: &[RangePair<u16>]) -> Vec<RangePair<u16>> {
+    merge_verse_ids(this.iter().chain(other).copied().collect())
+}
+
+pub(crate) fn intersect_verse_ids(this: &[RangePair<u16>], other: &[RangePair<u16>]) -> Vec<RangePair<u16>> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while let (Some(a), Some(b)) = (this.get(i), other.get(j)) {
+        let start = a.start.max(b.start);
+        let end = a.end.min(b.end);
+        if start <= end {
+            result.push(RangePair::new(start, end));
+        }
+        // whichever ends first cannot overlap with anything else
+        if a.end < b.end { i += 1; } else { j += 1; }
+    }
+    result
+}
+
+pub(crate) fn subtract_verse_ids(this: &[RangePair<u16>], other: &[RangePair<u16>]) -> Vec<RangePair<u16>> {
+    let mut result = Vec::new();
+    let mut other = other.iter().peekable();
+    for span in this {
+        let mut start = span.start;
+        // skip what ends before this span
+        while other.next_if(|removed| removed.end < start).is_some() {}
+        // cut out everything that starts within this span
+        while let Some(removed) = other.peek().filter(|removed| removed.start <= span.end) {
+            if start < removed.start {
+                result.push(RangePair::new(start, removed.start - 1));
+            }
+            if removed.end >= span.end {
+                // it may still remove some of the next span
+                start = span.end + 1;
+                break;
+            }
+            start = removed.end + 1;
+            other.next();
+        }
+        if start <= span.end {
+            result.push(RangePair::new(start, span.end));
+        }
+    }
+    result
+}
+
+/// - The results are always normalized (see [`BookPassageSegments::normalize`])
+/// - These methods validate the book/chapter/verse numbers
+impl BookPassageSegments {
+    fn check_same_book(&self, other: &BookPassageSegments) -> Result<(), String> {
+        if self.book != other.book {
+            Err(format!("Cannot combine passages from 'Book {}' and 'Book {}'", self.book, other.book))?
+        }
+        Ok(())
+    }
+
+    /// - Every verse in either passage
+    /// - Both passages must be in the same book
+    pub fn union(&self, other: &BookPassageSegments) -> Result<Self, String> {
+        self.check_same_book(other)?;
+        let ids = union_verse_ids(&self.merged_verse_ids()?, &other.merged_verse_ids()?);
+        Self::from_merged_verse_ids(self.book, ids)
+    }
+
+    /// - Every verse in both passages
+    /// - Passages in different books have nothing in common
+    pub fn intersection(&self, other: &BookPassageSegments) -> Result<Self, String> {
+        let this = self.merged_verse_ids()?;
+        let ids = if self.book == other.book {
+            intersect_verse_ids(&this, &other.merged_verse_ids()?)
+        } else {
+            vec![]
+        };
+        Self::from_merged_verse_ids(self.book, ids)
+    }
+
+    /// - Every verse in this passage that is not in the other one
+    /// - Passages in different books have nothing in common
+    pub fn difference(&self, other: &BookPassageSegments) -> Result<Self, String> {
+        let this = self.merged_verse_ids()?;
+        let ids = if self.book == other.book {
+            subtract_verse_ids(&this, &other.merged_verse_ids()?)
+        } else {
+            this
+        };
+        Self::from_merged_verse_ids(self.book, ids)
+    }
+
+    /// - Every verse in exactly one of the passages
+    /// - Both passages must be in the same book
+    pub fn symmetric_difference(&self, other: &BookPassageSegments) -> Result<Self, String> {
+        self.check_same_book(other)?;
+        let (this, other) = (self.merged_verse_ids()?, other.merged_verse_ids()?);
+        let ids = union_verse_ids(
+            &subtract_verse_ids(&this, &other),
+            &subtract_verse_ids(&other, &this),
+        );
+        Self::from_merged_verse_ids(self.book, ids)
+    }
+}
+
+/// - The book is required to know how many verses are in each chapter
+/// - See the [`BookPassageSegments`] methods of the same name
+impl PassageSegments {
+    pub fn union(&self, other: &PassageSegments, book: u8) -> Result<Self, String> {
+        Ok(self.clone().with_book(book).union(&other.clone().with_book(book))?.segments)
+    }
+
+    pub fn intersection(&self, other: &PassageSegments, book: u8) -> Result<Self, String> {
+        Ok(self.clone().with_book(book).intersection(&other.clone().with_book(book))?.segments)
+    }
+
+    pub fn difference(&self, other: &PassageSegments, book: u8) -> Result<Self, String> {
+        Ok(self.clone().with_book(book).difference(&other.clone().with_book(book))?.segments)
+    }
+
+    pub fn symmetric_difference(&self, other: &PassageSegments, book: u8) -> Result<Self, String> {
+        Ok(self.clone().with_book(book).symmetric_difference(&other.clone().with_book(book))?.segments)
+    }
+}
+
+#[cfg(test)]
+mod set_operations_tests {
+    use crate::{passage_segments::range_pair::RangePair, segment::PassageSegment, segments::{BookPassageSegments, PassageSegments}};
+
+    use super::{intersect_verse_ids, subtract_verse_ids};
+
+    fn ids(spans: &[(u16, u16)]) -> Vec<RangePair<u16>> {
+        spans.iter().map(|&(start, end)| RangePair::new(start, end)).collect()
+    }
+
+    fn passage(book: u8, input: &str) -> BookPassageSegments {
+        BookPassageSegments::parse(book, input).unwrap()
+    }
+
+    #[test]
+    fn intersect() {
+        assert_eq!(
+            intersect_verse_ids(&ids(&[(1, 5), (8, 12)]), &ids(&[(3, 9), (11, 20)])),
+            ids(&[(3, 5), (8, 9), (11, 12)])
+        );
+        assert_eq!(
+            intersect_verse_ids(&ids(&[(1, 5)]), &ids(&[(6, 9)])),
+            ids(&[])
+        );
+    }
+
+    #[test]
+    fn subtract() {
+        assert_eq!(
+            subtract_verse_ids(&ids(&[(1, 10)]), &ids(&[(3, 4), (6, 7)])),
+            ids(&[(1, 2), (5, 5), (8, 10)])
+        );
+        assert_eq!(
+            subtract_verse_ids(&ids(&[(1, 5), (8, 12)]), &ids(&[(4, 9)])),
+            ids(&[(1, 3), (10, 12)])
+        );
+        assert_eq!(
+            subtract_verse_ids(&ids(&[(1, 5)]), &ids(&[(1, 5)])),
+            ids(&[])
+        );
+    }
+
+    #[test]
+    fn union() {
+        // Genesis 1:1-10 + 1:11-31 = Genesis 1
+        assert_eq!(
+            passage(1, "1:1-10").union(&passage(1, "1:11-31")).unwrap().segments.0,
+            vec![PassageSegment::full_chapter(1)]
+        );
+
+        // Genesis 1:1-10 + 2:1 (different chapters stay apart)
+        assert_eq!(
+            passage(1, "1:1-10").union(&passage(1, "2:1")).unwrap().segments.0,
+            vec![PassageSegment::chapter_verse_range(1, 1, 10), PassageSegment::chapter_verse(2, 1)]
+        );
+
+        // different books
+        assert!(passage(1, "1").union(&passage(2, "1")).is_err());
+    }
+
+    #[test]
+    fn intersection() {
+        // John 3 & John 3:16-4:2 = John 3:16-36
+        assert_eq!(
+            passage(43, "3").intersection(&passage(43, "3:16-4:2")).unwrap().segments.0,
+            vec![PassageSegment::chapter_verse_range(3, 16, 36)]
+        );
+
+        // different books
+        assert!(passage(1, "1").intersection(&passage(2, "1")).unwrap().segments.is_empty());
+    }
+
+    #[test]
+    fn difference() {
+        // John 3 - John 3:16 = John 3:1-15, 17-36
+        assert_eq!(
+            passage(43, "3").difference(&passage(43, "3:16")).unwrap().segments.0,
+            vec![PassageSegment::chapter_verse_range(3, 1, 15), PassageSegment::chapter_verse_range(3, 17, 36)]
+        );
+
+        // Genesis 1-2 - Genesis 1:5-2:3 = Genesis 1:1-4, 2:4-25
+        assert_eq!(
+            passage(1, "1-2").difference(&passage(1, "1:5-2:3")).unwrap().segments.0,
+            vec![PassageSegment::chapter_verse_range(1, 1, 4), PassageSegment::chapter_verse_range(2, 4, 25)]
+        );
+
+        // different books
+        assert_eq!(
+            passage(1, "1").difference(&passage(2, "1")).unwrap().segments.0,
+            vec![PassageSegment::full_chapter(1)]
+        );
+    }
+
+    #[test]
+    fn symmetric_difference() {
+        // John 3:1-20 ^ John 3:10-36 = John 3:1-9, 21-36
+        let this = PassageSegments::parse("3:1-20").unwrap();
+        let other = PassageSegments::parse("3:10-36").unwrap();
+        assert_eq!(
+            this.symmetric_difference(&other, 43).unwrap().0,
+            vec![PassageSegment::chapter_verse_range(3, 1, 9), PassageSegment::chapter_verse_range(3, 21, 36)]
+        );
+    }
+}
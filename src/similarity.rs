@@ -0,0 +1,88 @@
+use crate::{set_operations::intersect_verse_ids, segments::BookPassageSegments};
+
+/// - These count verses, so every kind of segment works (`John 3` shares 36 verses with `John 3:1-36`)
+/// - Verses shared by multiple segments of the same passage are only counted once
+/// - These methods validate the book/chapter/verse numbers
+impl BookPassageSegments {
+    /// - How many verses are in both passages
+    /// - Passages in different books have nothing in common
+    pub fn shared_verse_count(&self, other: &BookPassageSegments) -> Result<u16, String> {
+        let (this, that) = (self.merged_verse_ids()?, other.merged_verse_ids()?);
+        if self.book != other.book {
+            return Ok(0);
+        }
+        Ok(intersect_verse_ids(&this, &that).iter().map(|ids| ids.end - ids.start + 1).sum())
+    }
+
+    /// - How much of this passage the other one covers, from `0.0` to `1.0`
+    /// - `John 3:16-17` covers `0.5` of `John 3:16-19`
+    /// - An empty passage is not covered at all
+    pub fn coverage_by(&self, other: &BookPassageSegments) -> Result<f64, String> {
+        let total = self.verse_count()?;
+        if total == 0 {
+            return Ok(0.0);
+        }
+        Ok(self.shared_verse_count(other)? as f64 / total as f64)
+    }
+
+    /// - The shared verses divided by the verses in either passage, from `0.0` (nothing shared) to `1.0` (the same verses)
+    /// - Two empty passages share nothing, so they are `0.0`
+    pub fn jaccard_similarity(&self, other: &BookPassageSegments) -> Result<f64, String> {
+        let shared = self.shared_verse_count(other)?;
+        let either = self.verse_count()? + other.verse_count()? - shared;
+        if either == 0 {
+            return Ok(0.0);
+        }
+        Ok(shared as f64 / either as f64)
+    }
+}
+
+#[cfg(test)]
+mod similarity_tests {
+    use crate::segments::{BookPassageSegments, PassageSegments};
+
+    fn passage(book: u8, input: &str) -> BookPassageSegments {
+        BookPassageSegments::parse(book, input).unwrap()
+    }
+
+    #[test]
+    fn shared_verse_count() -> Result<(), String> {
+        // John 3 and John 3:16-4:2
+        assert_eq!(passage(43, "3").shared_verse_count(&passage(43, "3:16-4:2"))?, 21);
+        // full chapters and verse ranges
+        assert_eq!(passage(43, "3").shared_verse_count(&passage(43, "3:1-36"))?, 36);
+        // overlapping segments are only counted once
+        assert_eq!(passage(43, "3:1-10, 3:5-12").shared_verse_count(&passage(43, "3"))?, 12);
+        // different books
+        assert_eq!(passage(43, "3").shared_verse_count(&passage(42, "3"))?, 0);
+        assert!(passage(43, "30").shared_verse_count(&passage(43, "3")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn coverage() -> Result<(), String> {
+        assert_eq!(passage(43, "3:16-19").coverage_by(&passage(43, "3:16-17"))?, 0.5);
+        assert_eq!(passage(43, "3:16-17").coverage_by(&passage(43, "3"))?, 1.0);
+        assert_eq!(passage(43, "3").coverage_by(&passage(43, "4"))?, 0.0);
+        assert_eq!(PassageSegments::new().with_book(43).coverage_by(&passage(43, "3"))?, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn jaccard_similarity() -> Result<(), String> {
+        // John 3:1-20 and John 3:11-30 share 10 of 30 verses
+        assert_eq!(passage(43, "3:1-20").jaccard_similarity(&passage(43, "3:11-30"))?, 10.0 / 30.0);
+        assert_eq!(passage(43, "3").jaccard_similarity(&passage(43, "3:1-36"))?, 1.0);
+        assert_eq!(passage(43, "3").jaccard_similarity(&passage(42, "3"))?, 0.0);
+
+        let empty = PassageSegments::new().with_book(43);
+        assert_eq!(empty.jaccard_similarity(&empty)?, 0.0);
+
+        // ranking related passages
+        let current = passage(43, "3:1-21");
+        let mut related = vec![passage(43, "3:16"), passage(43, "3"), passage(43, "4"), passage(43, "2:23-3:8")];
+        related.sort_by(|a, b| b.jaccard_similarity(&current).unwrap().total_cmp(&a.jaccard_similarity(&current).unwrap()));
+        assert_eq!(related, vec![passage(43, "3"), passage(43, "2:23-3:8"), passage(43, "3:16"), passage(43, "4")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{book_manager::BookManager, book_segment::BookSegment, segment::PassageSegment, segments::BookPassageSegments};
+
+/// - The words used to read references aloud
+/// - Implement this for other languages (with a [`BookManager`] that has book names in that language)
+pub trait SpokenLocale {
+    /// - Ex: `thirteen`, `one hundred nineteen`
+    fn number(&self, n: u8) -> String;
+
+    /// - The word that replaces the number at the start of a book name
+    /// - Ex: `First` for `1 Corinthians`
+    fn book_ordinal(&self, n: u8) -> String;
+
+    /// - Ex: `chapter` or `chapters`
+    fn chapter(&self, plural: bool) -> &str;
+
+    /// - Ex: `verse` or `verses`
+    fn verse(&self, plural: bool) -> &str;
+
+    /// - What goes between the start and end of a range
+    /// - Ex: `through`
+    fn through(&self) -> &str;
+}
+
+/// - Ex: `First Corinthians, chapter thirteen, verses four through seven`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct English;
+
+const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+
+const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+
+impl SpokenLocale for English {
+    fn number(&self, n: u8) -> String {
+        let below_hundred = |n: u8| match (n / 10, n % 10) {
+            (0..=1, _) => ONES[n as usize].to_string(),
+            (tens, 0) => TENS[tens as usize].to_string(),
+            (tens, ones) => format!("{}-{}", TENS[tens as usize], ONES[ones as usize]),
+        };
+        match (n / 100, n % 100) {
+            (0, rest) => below_hundred(rest),
+            (hundreds, 0) => format!("{} hundred", ONES[hundreds as usize]),
+            (hundreds, rest) => format!("{} hundred {}", ONES[hundreds as usize], below_hundred(rest)),
+        }
+    }
+
+    fn book_ordinal(&self, n: u8) -> String {
+        match n {
+            1 => String::from("First"),
+            2 => String::from("Second"),
+            3 => String::from("Third"),
+            _ => self.number(n),
+        }
+    }
+
+    fn chapter(&self, plural: bool) -> &str {
+        if plural { "chapters" } else { "chapter" }
+    }
+
+    fn verse(&self, plural: bool) -> &str {
+        if plural { "verses" } else { "verse" }
+    }
+
+    fn through(&self) -> &str {
+        "through"
+    }
+}
+
+/// - The book name from the [`BookManager`], with a leading number read as an ordinal (`1 Corinthians` -> `First Corinthians`)
+/// - Returns an error if the [`BookManager`] does not know the book
+pub fn spoken_book_name(manager: &BookManager, book: u8, locale: &impl SpokenLocale) -> Result<String, String> {
+    let name = manager.book_id_to_name.get(&book).ok_or_else(|| format!("There is no 'Book {}' in the Bible", book))?;
+    let ordinal = name.split_once(' ')
+        .and_then(|(number, rest)| Some((number.parse::<u8>().ok()?, rest)));
+    Ok(match ordinal {
+        Some((number, rest)) => format!("{} {}", locale.book_ordinal(number), rest),
+        None => name.clone(),
+    })
+}
+
+impl PassageSegment {
+    /// - The segment read aloud, without the book
+    /// - Ex: `chapter thirteen, verses four through seven`
+    pub fn spoken(&self, locale: &impl SpokenLocale) -> String {
+        let n = |n: u8| locale.number(n);
+        match self {
+            PassageSegment::ChapterVerse(seg) => format!(
+                "{} {}, {} {}",
+                locale.chapter(false), n(seg.chapter), locale.verse(false), n(seg.verse)
+            ),
+            PassageSegment::ChapterVerseRange(seg) => format!(
+                "{} {}, {} {} {} {}",
+                locale.chapter(false), n(seg.chapter), locale.verse(true), n(seg.verses.start), locale.through(), n(seg.verses.end)
+            ),
+            PassageSegment::ChapterRange(seg) => format!(
+                "{} {}, {} {}, {} {} {}, {} {}",
+                locale.chapter(false), n(seg.start.chapter), locale.verse(false), n(seg.start.verse),
+                locale.through(),
+                locale.chapter(false), n(seg.end.chapter), locale.verse(false), n(seg.end.verse)
+            ),
+            PassageSegment::FullChapter(seg) => format!(
+                "{} {}",
+                locale.chapter(false), n(seg.chapter)
+            ),
+            PassageSegment::FullChapterRange(seg) => format!(
+                "{} {} {} {}",
+                locale.chapter(true), n(seg.start.chapter), locale.through(), n(seg.end.chapter)
+            ),
+        }
+    }
+}
+
+impl BookSegment<PassageSegment> {
+    /// - Ex: `First Corinthians, chapter thirteen, verses four through seven`
+    /// - Returns an error if the [`BookManager`] does not know the book
+    pub fn spoken(&self, manager: &BookManager, locale: &impl SpokenLocale) -> Result<String, String> {
+        Ok(format!("{}, {}", spoken_book_name(manager, self.book, locale)?, self.segment.spoken(locale)))
+    }
+}
+
+impl BookPassageSegments {
+    /// - Each segment is read in full, with a `;` between them for a pause
+    /// - Ex: `John, chapter three, verse sixteen; chapter four, verse one`
+    /// - Returns an error if the [`BookManager`] does not know the book
+    pub fn spoken(&self, manager: &BookManager, locale: &impl SpokenLocale) -> Result<String, String> {
+        let segments: Vec<String> = self.segments.iter().map(|seg| seg.spoken(locale)).collect();
+        Ok(format!("{}, {}", spoken_book_name(manager, self.book, locale)?, segments.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod spoken_tests {
+    use crate::{book_manager::DEFAULT_BOOK_MANAGER, book_segment::BookSegment, segment::PassageSegment, segments::BookPassageSegments};
+
+    use super::{spoken_book_name, English, SpokenLocale};
+
+    #[test]
+    fn numbers() {
+        assert_eq!(English.number(0), "zero");
+        assert_eq!(English.number(13), "thirteen");
+        assert_eq!(English.number(20), "twenty");
+        assert_eq!(English.number(36), "thirty-six");
+        assert_eq!(English.number(100), "one hundred");
+        assert_eq!(English.number(119), "one hundred nineteen");
+        assert_eq!(English.number(150), "one hundred fifty");
+        assert_eq!(English.number(176), "one hundred seventy-six");
+    }
+
+    #[test]
+    fn book_names() {
+        assert_eq!(spoken_book_name(&DEFAULT_BOOK_MANAGER, 46, &English).unwrap(), "First Corinthians");
+        assert_eq!(spoken_book_name(&DEFAULT_BOOK_MANAGER, 64, &English).unwrap(), "Third John");
+        assert_eq!(spoken_book_name(&DEFAULT_BOOK_MANAGER, 22, &English).unwrap(), "Song of Solomon");
+        assert!(spoken_book_name(&DEFAULT_BOOK_MANAGER, 67, &English).is_err());
+    }
+
+    #[test]
+    fn segments() {
+        let spoken = |seg: BookSegment<PassageSegment>| seg.spoken(&DEFAULT_BOOK_MANAGER, &English).unwrap();
+        assert_eq!(spoken(BookSegment::chapter_verse(43, 3, 16).generalize()), "John, chapter three, verse sixteen");
+        assert_eq!(spoken(BookSegment::chapter_verse_range(46, 13, 4, 7).generalize()), "First Corinthians, chapter thirteen, verses four through seven");
+        assert_eq!(spoken(BookSegment::chapter_range(43, 3, 36, 4, 2).generalize()), "John, chapter three, verse thirty-six, through chapter four, verse two");
+        assert_eq!(spoken(BookSegment::full_chapter(19, 119).generalize()), "Psalms, chapter one hundred nineteen");
+        assert_eq!(spoken(BookSegment::full_chapter_range(1, 1, 3).generalize()), "Genesis, chapters one through three");
+    }
+
+    #[test]
+    fn passages() {
+        let passage = BookPassageSegments::parse(43, "3:16, 4:1").unwrap();
+        assert_eq!(passage.spoken(&DEFAULT_BOOK_MANAGER, &English).unwrap(), "John, chapter three, verse sixteen; chapter four, verse one");
+    }
+}
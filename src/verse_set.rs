@@ -0,0 +1,304 @@
+use std::fmt::Debug;
+
+use serde::{de::Error, Deserialize, Serialize};
+
+use crate::{book_chapter_verse::{BookChapterVerse, LAST_VERSE}, book_segment::BookSegment, compare::SegmentCompare, passage_segments::range_pair::RangePair, segments::BookPassageSegments};
+
+const WORD_COUNT: usize = (LAST_VERSE as usize).div_ceil(64);
+
+/// - A set of verses across the whole Bible, with one bit for each global verse id (see [`BookChapterVerse::as_verse`])
+/// - Set operations and counting are much faster than with lists of segments, so this is meant for things like coverage maps
+/// - It serializes as run-length ranges of global verse ids (`[[1, 31], [43, 43]]`), so it stays small for things like reading progress
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct VerseSet {
+    /// - Bit `n` is the verse with global id `n + 1`
+    words: Vec<u64>,
+}
+
+impl Default for VerseSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerseSet {
+    pub fn new() -> Self {
+        Self { words: vec![0; WORD_COUNT] }
+    }
+
+    /// - Every verse from Genesis 1:1 to Revelation 22:21
+    pub fn full() -> Self {
+        let mut set = Self::new();
+        set.insert_verse_ids(RangePair::new(1, LAST_VERSE));
+        set
+    }
+
+    fn position(id: u16) -> (usize, u64) {
+        let idx = (id - 1) as usize;
+        (idx / 64, 1 << (idx % 64))
+    }
+
+    /// - Returns whether the verse was not already in the set
+    pub fn insert(&mut self, verse: BookChapterVerse) -> bool {
+        let (word, bit) = Self::position(verse.as_verse());
+        let is_new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        is_new
+    }
+
+    /// - Returns whether the verse was in the set
+    pub fn remove(&mut self, verse: BookChapterVerse) -> bool {
+        let (word, bit) = Self::position(verse.as_verse());
+        let was_present = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        was_present
+    }
+
+    pub fn contains(&self, verse: BookChapterVerse) -> bool {
+        let (word, bit) = Self::position(verse.as_verse());
+        self.words[word] & bit != 0
+    }
+
+    /// - The ids must already be valid
+    fn insert_verse_ids(&mut self, ids: RangePair<u16>) {
+        for id in ids.start..=ids.end {
+            let (word, bit) = Self::position(id);
+            self.words[word] |= bit;
+        }
+    }
+
+    /// - This method validates the book/chapter/verse numbers
+    pub fn insert_segment(&mut self, segment: &BookSegment<impl SegmentCompare>) -> Result<(), String> {
+        self.insert_verse_ids(segment.verse_ids()?);
+        Ok(())
+    }
+
+    /// - This method validates the book/chapter/verse numbers
+    pub fn insert_passage(&mut self, passage: &BookPassageSegments) -> Result<(), String> {
+        for ids in passage.merged_verse_ids()? {
+            self.insert_verse_ids(ids);
+        }
+        Ok(())
+    }
+
+    /// - How many verses are in the set
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    fn combine(&self, other: &VerseSet, op: impl Fn(u64, u64) -> u64) -> Self {
+        Self {
+            words: self.words.iter().zip(&other.words).map(|(&this, &other)| op(this, other)).collect(),
+        }
+    }
+
+    /// - Every verse in either set
+    pub fn union(&self, other: &VerseSet) -> Self {
+        self.combine(other, |this, other| this | other)
+    }
+
+    /// - Every verse in both sets
+    pub fn intersection(&self, other: &VerseSet) -> Self {
+        self.combine(other, |this, other| this & other)
+    }
+
+    /// - Every verse in this set that is not in the other one
+    pub fn difference(&self, other: &VerseSet) -> Self {
+        self.combine(other, |this, other| this & !other)
+    }
+
+    /// - Every verse in exactly one of the sets
+    pub fn symmetric_difference(&self, other: &VerseSet) -> Self {
+        self.combine(other, |this, other| this ^ other)
+    }
+
+    /// - The global verse ids in the set, in order
+    fn iter_verse_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_idx, &word)| {
+            let mut remaining = word;
+            std::iter::from_fn(move || {
+                if remaining == 0 { return None; }
+                let bit = remaining.trailing_zeros();
+                remaining &= remaining - 1;
+                Some((word_idx * 64 + bit as usize + 1) as u16)
+            })
+        })
+    }
+
+    /// - Every verse in the set, in order
+    pub fn iter(&self) -> impl Iterator<Item = BookChapterVerse> + '_ {
+        self.iter_verse_ids().map(|id| BookChapterVerse::from_verse(id).expect("The set only holds valid verse ids"))
+    }
+
+    /// - Every run of consecutive global verse ids, in order
+    /// - Runs continue across chapters and books
+    pub fn runs(&self) -> Vec<RangePair<u16>> {
+        let mut runs: Vec<RangePair<u16>> = Vec::new();
+        for id in self.iter_verse_ids() {
+            match runs.last_mut() {
+                Some(last) if last.end + 1 == id => last.end = id,
+                _ => runs.push(RangePair::from_point(id)),
+            }
+        }
+        runs
+    }
+
+    /// - Builds a set from runs of global verse ids (see [`VerseSet::runs`])
+    pub fn from_runs(runs: impl IntoIterator<Item = RangePair<u16>>) -> Result<Self, String> {
+        let mut set = Self::new();
+        for run in runs {
+            if run.start == 0 || run.end > LAST_VERSE || run.start > run.end {
+                Err(format!("'{}-{}' is not a valid range of verses", run.start, run.end))?
+            }
+            set.insert_verse_ids(run);
+        }
+        Ok(set)
+    }
+
+    /// - Compresses the set back into the fewest segments (see [`BookPassageSegments::from_verses`])
+    /// - There is one [`BookPassageSegments`] for each book, in the order of the books
+    pub fn to_passages(&self) -> Vec<BookPassageSegments> {
+        BookPassageSegments::from_merged_verse_ids_across_books(self.runs())
+            .expect("The set only holds valid verse ids")
+    }
+}
+
+impl TryFrom<&BookPassageSegments> for VerseSet {
+    type Error = String;
+
+    fn try_from(passage: &BookPassageSegments) -> Result<Self, Self::Error> {
+        let mut set = Self::new();
+        set.insert_passage(passage)?;
+        Ok(set)
+    }
+}
+
+impl FromIterator<BookChapterVerse> for VerseSet {
+    fn from_iter<T: IntoIterator<Item = BookChapterVerse>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<BookChapterVerse> for VerseSet {
+    fn extend<T: IntoIterator<Item = BookChapterVerse>>(&mut self, iter: T) {
+        for verse in iter {
+            self.insert(verse);
+        }
+    }
+}
+
+/// - Shows the runs of global verse ids instead of every word
+impl Debug for VerseSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.runs().iter().map(|run| run.start..=run.end))
+            .finish()
+    }
+}
+
+impl Serialize for VerseSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        let runs: Vec<[u16; 2]> = self.runs().iter().map(|run| [run.start, run.end]).collect();
+        runs.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VerseSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        let runs = Vec::<[u16; 2]>::deserialize(deserializer)?;
+        VerseSet::from_runs(runs.into_iter().map(|[start, end]| RangePair::new(start, end)))
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod verse_set_tests {
+    use crate::{book_chapter_verse::BookChapterVerse, book_segment::BookSegment, passage_segments::range_pair::RangePair, segment::PassageSegment, segments::BookPassageSegments};
+
+    use super::VerseSet;
+
+    fn verse(book: u8, chapter: u8, verse: u8) -> BookChapterVerse {
+        BookChapterVerse::new(book, chapter, verse).unwrap()
+    }
+
+    fn set(book: u8, input: &str) -> VerseSet {
+        VerseSet::try_from(&BookPassageSegments::parse(book, input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = VerseSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(verse(43, 3, 16)));
+        assert!(!set.insert(verse(43, 3, 16)));
+        assert!(set.contains(verse(43, 3, 16)));
+        assert!(!set.contains(verse(43, 3, 17)));
+        assert_eq!(set.len(), 1);
+
+        assert!(set.insert(BookChapterVerse::first()));
+        assert!(set.insert(BookChapterVerse::last()));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![BookChapterVerse::first(), verse(43, 3, 16), BookChapterVerse::last()]);
+
+        assert!(set.remove(verse(43, 3, 16)));
+        assert!(!set.remove(verse(43, 3, 16)));
+        assert_eq!(set.len(), 2);
+
+        assert_eq!(VerseSet::full().len(), 31_102);
+    }
+
+    #[test]
+    fn set_operations() {
+        // John 3:1-20 and John 3:10-36
+        let (this, other) = (set(43, "3:1-20"), set(43, "3:10-36"));
+        assert_eq!(this.union(&other), set(43, "3"));
+        assert_eq!(this.intersection(&other), set(43, "3:10-20"));
+        assert_eq!(this.difference(&other), set(43, "3:1-9"));
+        assert_eq!(this.symmetric_difference(&other), set(43, "3:1-9, 3:21-36"));
+        assert_eq!(this.union(&other).len(), 36);
+    }
+
+    #[test]
+    fn passages() {
+        // Genesis 50 and Exodus 1:1-2 are split by book
+        let mut set = set(1, "50");
+        set.insert_segment(&BookSegment::chapter_verse_range(2, 1, 1, 2)).unwrap();
+        assert_eq!(set.runs().len(), 1);
+
+        let passages = set.to_passages();
+        assert_eq!(passages.len(), 2);
+        assert_eq!((passages[0].book, passages[0].segments.0.clone()), (1, vec![PassageSegment::full_chapter(50)]));
+        assert_eq!((passages[1].book, passages[1].segments.0.clone()), (2, vec![PassageSegment::chapter_verse_range(1, 1, 2)]));
+
+        // overlapping segments are compressed
+        let passages = self::set(43, "3:1-20, 3:10-36, 4:1").to_passages();
+        assert_eq!(passages[0].segments.0, vec![PassageSegment::chapter_range(3, 1, 4, 1)]);
+
+        assert!(VerseSet::try_from(&BookPassageSegments::parse(43, "3:37").unwrap()).is_err());
+    }
+
+    #[test]
+    fn serialize() {
+        let set = self::set(1, "1, 2:1").union(&self::set(43, "3:16"));
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, format!("[[1,32],[{id},{id}]]", id = verse(43, 3, 16).as_verse()));
+        assert_eq!(serde_json::from_str::<VerseSet>(&json).unwrap(), set);
+
+        assert!(serde_json::from_str::<VerseSet>("[[5,3]]").is_err());
+        assert!(serde_json::from_str::<VerseSet>("[[0,3]]").is_err());
+        assert!(serde_json::from_str::<VerseSet>("[[1,31103]]").is_err());
+
+        assert_eq!(VerseSet::from_runs([RangePair::new(1, 3)]).unwrap().len(), 3);
+    }
+}